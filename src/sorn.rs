@@ -1,18 +1,50 @@
 use std::cell::RefCell;
-use std::f64::INFINITY;
-use std::f64::NEG_INFINITY;
-use std::collections::HashMap;
 use std::rc::Rc;
 
-use crate::sornset::SornSet;
+use crate::sornset::{SornSet, SornSetRegistry};
 use crate::sornset::SornValue;
 
 /* Change the type to u64 if u need more bits */
 pub type SornBitsType = u128;
 
+/* Type of the interval endpoints, the f32 feature halves the memory of large sets */
+#[cfg(not(feature = "f32"))]
+pub type Real = f64;
+#[cfg(feature = "f32")]
+pub type Real = f32;
+
 pub fn sorn_max_bits() -> usize
 {
-	(std::mem::size_of::<SornBitsType>() * 8).try_into().unwrap()
+	std::mem::size_of::<SornBitsType>() * 8
+}
+
+/*
+Sorts by the lowest and then the highest value of each Sorn, empty Sorns last. This is
+an order for display, overlapping Sorns have no real order, see lt.
+*/
+pub fn sort_sorns(sorns: &mut [Sorn])
+{
+	let key = |sorn: &Sorn|
+	{
+		let ranges = sorn.ranges_ref();
+		let min = ranges.iter().map(SornValue::min).reduce(Real::min);
+		let max = ranges.iter().map(SornValue::max).reduce(Real::max);
+
+		(min.is_none(), min.unwrap_or(0.0), max.unwrap_or(0.0))
+	};
+
+	sorns.sort_by(|a, b|
+	{
+		let (a, b) = (key(a), key(b));
+
+		a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)).then(a.2.total_cmp(&b.2))
+	});
+}
+
+/* Bits of the first len elements, saturating once len fills SornBitsType */
+fn mask_for_len(len: usize) -> SornBitsType
+{
+	if len >= sorn_max_bits() { SornBitsType::MAX } else { (1 << len) - 1 }
 }
 
 #[derive(Debug)]
@@ -20,6 +52,145 @@ pub struct Sorn
 {
 	pub bits: SornBitsType,
 	pub sorn_set: Rc<RefCell<SornSet>>,
+
+	/* Last operation with (self.bits, operand.bits) before it was applied */
+	#[cfg(feature = "trace")]
+	provenance: Option<(Op, SornBitsType, SornBitsType)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op
+{
+	Add,
+	Sub,
+	Mul,
+	Div,
+}
+
+impl Op
+{
+	/* Maps the operation names used by checked_op and gen_table */
+	pub fn from_name(name: &str) -> Option<Op>
+	{
+		match name
+		{
+			"add" => Some(Op::Add),
+			"sub" => Some(Op::Sub),
+			"mul" => Some(Op::Mul),
+			"div" => Some(Op::Div),
+			_ => None,
+		}
+	}
+
+	pub fn name(self) -> &'static str
+	{
+		match self
+		{
+			Op::Add => "add",
+			Op::Sub => "sub",
+			Op::Mul => "mul",
+			Op::Div => "div",
+		}
+	}
+}
+
+/* Outcome of comparing Sorns, Unknown if the comparison holds for some values and not for others */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trilean
+{
+	True,
+	False,
+	Unknown,
+}
+
+/* What shift_up and shift_down do with a bucket moved past the edge of the set */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShiftMode
+{
+	/* The bucket vanishes */
+	Drop,
+	/* The bucket stays on the edge element */
+	Saturate,
+	/* The bucket continues at the other end, for cyclic domains like angles */
+	Wrap,
+}
+
+/* How Sorn::project maps a value onto the buckets of a set */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectionMode
+{
+	/* Every bucket the value overlaps, never loses a value, same as sorn_to_bits */
+	Outward,
+	/* Only buckets the value covers completely, may lose the values near its ends */
+	Inward,
+	/* The single bucket nearest to the center of the value */
+	Nearest,
+}
+
+/*
+Result of Sorn::checked_op_explained. min_from and max_from are the (self, operand)
+bucket indices whose combination reached the lowest and highest bound of the result,
+None if no finite pair was combined.
+*/
+#[derive(Debug)]
+pub struct OpResult
+{
+	pub result: Sorn,
+	pub min_from: Option<(usize, usize)>,
+	pub max_from: Option<(usize, usize)>,
+}
+
+/*
+Result of Sorn::checked_add_report. The widths are the distance from the lowest to the
+highest value, 0 for an empty Sorn and infinite if an unbounded bucket is active.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpReport
+{
+	pub bits: SornBitsType,
+	pub cardinality_before: usize,
+	pub cardinality_after: usize,
+	pub width_before: Real,
+	pub width_after: Real,
+}
+
+/* The active elements of a Sorn, read straight from its set without copying them */
+pub struct RangesView<'a>
+{
+	set: std::cell::Ref<'a, SornSet>,
+	bits: SornBitsType,
+}
+
+impl RangesView<'_>
+{
+	pub fn iter(&self) -> impl Iterator<Item = &SornValue> + '_
+	{
+		self.set.sets.iter().enumerate().filter(|(i, _)| self.bits & (1 << i) != 0).map(|(_, value)| value)
+	}
+
+	pub fn len(&self) -> usize
+	{
+		self.bits.count_ones() as usize
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		self.bits == 0
+	}
+
+	/* The i-th active element, not the i-th element of the set */
+	pub fn get(&self, i: usize) -> Option<&SornValue>
+	{
+		self.iter().nth(i)
+	}
+}
+
+impl std::fmt::Debug for RangesView<'_>
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		f.debug_list().entries(self.iter()).finish()
+	}
 }
 
 #[derive(Debug)]
@@ -27,27 +198,107 @@ pub enum SornErrors
 {
 	NotInRange,
 	DifferentSornSets,
+	SetTooLarge,
+	DimensionMismatch,
+	InvalidRange,
+	DivByZero,
+	PrecisionLoss,
+	NotSingleBucket,
 }
 
-impl Sorn
+impl std::fmt::Display for SornErrors
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
+	{
+		match self
+		{
+			SornErrors::NotInRange => write!(f, "value not representable in this SORN set"),
+			SornErrors::DifferentSornSets => write!(f, "operands belong to different SORN sets"),
+			SornErrors::SetTooLarge => write!(f, "SORN set has more elements than SornBitsType has bits"),
+			SornErrors::DimensionMismatch => write!(f, "operands have mismatching dimensions"),
+			SornErrors::InvalidRange => write!(f, "range needs end > start and a positive step"),
+			SornErrors::DivByZero => write!(f, "divisor may be zero and the set has no infinity"),
+			SornErrors::PrecisionLoss => write!(f, "integer is not exactly representable as a float"),
+			SornErrors::NotSingleBucket => write!(f, "Sorn has no or more than one active bucket"),
+		}
+	}
+}
+
+impl std::error::Error for SornErrors
+{}
+
+impl Default for Sorn
 {
-	pub fn default() -> Self
+	fn default() -> Self
 	{
 		Sorn
 		{
 			bits: 0,
 			sorn_set: Rc::new(RefCell::new(SornSet::default())),
+
+			#[cfg(feature = "trace")]
+			provenance: None,
 		}
 	}
+}
 
+impl Sorn
+{
 	pub fn new(set: Rc<RefCell<SornSet>>) -> Self
 	{
 		/* Create and return the Sorntype */
 		Sorn
 		{
 			bits: 0,
-			sorn_set: set
+			sorn_set: set,
+
+			#[cfg(feature = "trace")]
+			provenance: None,
+		}
+	}
+
+	/* Every bucket of the set active, the element that knows nothing about the value */
+	pub fn full(set: Rc<RefCell<SornSet>>) -> Self
+	{
+		let mut sorn = Sorn::new(set);
+		sorn.bits = sorn.valid_mask();
+
+		return sorn;
+	}
+
+	/* No active bucket, the identity of union and the result of any operation with it, same as new */
+	pub fn empty(set: Rc<RefCell<SornSet>>) -> Self
+	{
+		return Sorn::new(set);
+	}
+
+	/* The multiplicative identity [1], None if 1 is no exact point of the set */
+	pub fn one(set: Rc<RefCell<SornSet>>) -> Option<Self>
+	{
+		let bits = set.borrow().one_bit;
+
+		return Sorn::identity(set, bits, 1.0);
+	}
+
+	/* The additive identity [0], None if 0 is no exact point of the set */
+	pub fn zero(set: Rc<RefCell<SornSet>>) -> Option<Self>
+	{
+		let bits = set.borrow().zero_bit;
+
+		return Sorn::identity(set, bits, 0.0);
+	}
+
+	fn identity(set: Rc<RefCell<SornSet>>, bits: SornBitsType, value: Real) -> Option<Self>
+	{
+		let mut sorn = Sorn::new(set);
+		sorn.bits = bits;
+
+		if sorn.try_into_value().ok()? != SornValue::Exact(value)
+		{
+			return None;
 		}
+
+		return Some(sorn);
 	}
 
 	pub fn new_array<const N: usize>(custom_set: Rc<RefCell<SornSet>>) -> [Self; N]
@@ -70,25 +321,214 @@ impl Sorn
 		return result;
 	}
 
-	pub fn set_value(&mut self, value: SornValue)
+	pub fn from_f64(set: Rc<RefCell<SornSet>>, x: Real) -> Sorn
+	{
+		return Sorn::from_sornvalue(set, SornValue::Exact(x));
+	}
+
+	/*
+	Projects n like from_f64_clamped, so out of range integers saturate at the edges.
+	Fails with PrecisionLoss if n has no exact float representation, above 2^53 for f64.
+	*/
+	pub fn from_i64(set: Rc<RefCell<SornSet>>, n: i64) -> Result<Sorn, SornErrors>
+	{
+		let x = n as Real;
+
+		if x as i128 != n as i128
+		{
+			return Err(SornErrors::PrecisionLoss);
+		}
+
+		return Ok(Sorn::from_f64_clamped(set, x));
+	}
+
+	pub fn from_u64(set: Rc<RefCell<SornSet>>, n: u64) -> Result<Sorn, SornErrors>
+	{
+		let x = n as Real;
+
+		if x as i128 != n as i128
+		{
+			return Err(SornErrors::PrecisionLoss);
+		}
+
+		return Ok(Sorn::from_f64_clamped(set, x));
+	}
+
+	/* The elements of the set equal to one of values active, values that are no element are ignored */
+	pub fn with_active(set: Rc<RefCell<SornSet>>, values: &[SornValue]) -> Sorn
+	{
+		let mut sorn = Sorn::new(set.clone());
+
+		sorn.bits = sorn.with_set(|set| set.sets.iter().enumerate()
+			.filter(|(_, item)| values.contains(item))
+			.fold(0, |bits, (i, _)| bits | 1 << i));
+
+		return sorn;
+	}
+
+	/* One entry per element of the set, fails with DimensionMismatch if the length differs */
+	pub fn from_mask(set: Rc<RefCell<SornSet>>, mask: &[bool]) -> Result<Sorn, SornErrors>
+	{
+		if mask.len() != set.borrow().len()
+		{
+			return Err(SornErrors::DimensionMismatch);
+		}
+
+		if mask.len() > sorn_max_bits()
+		{
+			return Err(SornErrors::SetTooLarge);
+		}
+
+		let mut sorn = Sorn::new(set.clone());
+		sorn.bits = mask.iter().enumerate().filter(|(_, active)| **active).fold(0, |bits, (i, _)| bits | 1 << i);
+
+		return Ok(sorn);
+	}
+
+	/* value projected onto set by mode, Nearest falls back to Outward for unbounded values */
+	pub fn project(set: Rc<RefCell<SornSet>>, value: SornValue, mode: ProjectionMode) -> Sorn
+	{
+		let mut sorn = Sorn::new(set.clone());
+
+		sorn.bits = match mode
+		{
+			ProjectionMode::Outward => Sorn::sorn_to_bits(set.clone(), &value),
+			ProjectionMode::Inward => sorn.with_set(|set| set.sets.iter().enumerate()
+				.filter(|(_, item)| value.encloses(item))
+				.fold(0, |bits, (i, _)| bits | 1 << i)),
+			ProjectionMode::Nearest => match value.closed_bounds()
+			{
+				Some((min, _, max, _)) if min.is_finite() && max.is_finite() => Sorn::from_f64_clamped(set.clone(), min / 2.0 + max / 2.0).bits,
+				_ => Sorn::sorn_to_bits(set.clone(), &value),
+			},
+		};
+
+		return sorn;
+	}
+
+	/* Buckets of all values ORed together, values outside the set and NaN add nothing */
+	pub fn from_scalars(set: Rc<RefCell<SornSet>>, values: &[Real]) -> Sorn
+	{
+		let mut sorn = Sorn::new(set.clone());
+
+		for value in values
+		{
+			sorn.bits |= Sorn::from_f64(set.clone(), *value).bits;
+		}
+
+		return sorn;
+	}
+
+	/*
+	Like from_f64, but a value that is in no bucket activates the nearest bucket instead,
+	so out of range inputs saturate at the edges of the set. Only NaN stays empty.
+	*/
+	pub fn from_f64_clamped(set: Rc<RefCell<SornSet>>, x: Real) -> Sorn
+	{
+		let mut result = Sorn::from_f64(set.clone(), x);
+
+		if result.bits == 0 && !x.is_nan()
+		{
+			let set = set.borrow();
+			let bounds = || set.sets.iter().enumerate().filter_map(|(i, item)| item.closed_bounds().map(|bounds| (i, bounds)));
+			let lowest = bounds().map(|(_, (min, _, _, _))| min).fold(Real::INFINITY, Real::min);
+			let highest = bounds().map(|(_, (_, _, max, _))| max).fold(Real::NEG_INFINITY, Real::max);
+
+			/* Past the edges the distances of far away values round to the same number, so pick the edge bucket directly */
+			let nearest = if x > highest
+			{
+				bounds().filter(|(_, (_, _, max, _))| *max == highest).max_by_key(|(_, (_, _, _, max_closed))| *max_closed).map(|(i, _)| i)
+			}
+			else if x < lowest
+			{
+				bounds().filter(|(_, (min, _, _, _))| *min == lowest).max_by_key(|(_, (_, min_closed, _, _))| *min_closed).map(|(i, _)| i)
+			}
+			else
+			{
+				/* On equal distance a bucket that contains its nearest endpoint wins */
+				let distance = |(min, min_closed, max, max_closed): (Real, bool, Real, bool)|
+				{
+					if x < min { (min - x, !min_closed) } else { (x - max, !max_closed) }
+				};
+
+				bounds()
+					.map(|(i, bounds)| (i, distance(bounds)))
+					.min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+					.map(|(i, _)| i)
+			};
+
+			if let Some(i) = nearest
+			{
+				result.bits = 1 << i;
+			}
+		}
+
+		return result;
+	}
+
+	/* Sorn covering every bucket that overlaps the interval between lo and hi */
+	pub fn from_interval(set: Rc<RefCell<SornSet>>, lo: Real, hi: Real, left_open: bool, right_open: bool) -> Sorn
 	{
-		let mut pos = 0;
+		assert!(lo <= hi, "Tried to create a Sorn from an interval with lo > hi");
+
+		let mut result = Sorn::new(set.clone());
+		let bits = Sorn::interval_to_bits(set.clone(), lo, hi, left_open, right_open);
+
+		result.set_bits(bits).unwrap();
 
-		for (i, set) in self.sorn_set.borrow().sets.iter().enumerate()
+		return result;
+	}
+
+	fn interval_to_bits(set: Rc<RefCell<SornSet>>, lo: Real, hi: Real, left_open: bool, right_open: bool) -> SornBitsType
+	{
+		if lo == hi
 		{
-			if *set == value
+			/* A degenerate interval with an open end contains nothing */
+			if left_open || right_open { 0 } else { Sorn::sorn_to_bits(set.clone(), &SornValue::Exact(lo)) }
+		}
+		else
+		{
+			match (left_open, right_open)
 			{
-				pos = 1 << i;
-				break;
+				(true, true) => Sorn::sorn_to_bits(set.clone(), &SornValue::Open((lo, hi))),
+				(true, false) => Sorn::sorn_to_bits(set.clone(), &SornValue::OpenLeft((lo, hi))),
+				(false, true) => Sorn::sorn_to_bits(set.clone(), &SornValue::OpenRight((lo, hi))),
+				/* There is no closed interval value, so add the right end separately */
+				(false, false) => Sorn::sorn_to_bits(set.clone(), &SornValue::OpenRight((lo, hi)))
+					| Sorn::sorn_to_bits(set.clone(), &SornValue::Exact(hi)),
 			}
 		}
+	}
+
+	/* The operation that produced this Sorn, with the operand bits it was applied to */
+	#[cfg(feature = "trace")]
+	pub fn last_op(&self) -> Option<(Op, SornBitsType, SornBitsType)>
+	{
+		self.provenance
+	}
+
+	/* Every bit that belongs to an element of the set */
+	pub fn valid_mask(&self) -> SornBitsType
+	{
+		return mask_for_len(self.with_set(SornSet::len));
+	}
+
+	/* Runs f with the set borrowed, the borrow ends when f returns */
+	pub fn with_set<R>(&self, f: impl FnOnce(&SornSet) -> R) -> R
+	{
+		return f(&self.sorn_set.borrow());
+	}
 
-		self.set_bits(pos);
+	pub fn set_value(&mut self, value: SornValue)
+	{
+		let pos = self.with_set(|set| set.sets.iter().position(|item| *item == value));
+
+		let _ = self.set_bits(pos.map_or(0, |i| 1 << i));
 	}
 
 	pub fn set_bits(&mut self, bits: SornBitsType) -> Result<(), SornErrors>
 	{
-		if bits.leading_zeros() < (sorn_max_bits() - self.sorn_set.borrow().len()) as u32
+		if bits & !self.valid_mask() != 0
 		{
 			return Err(SornErrors::NotInRange);
 		}
@@ -98,17 +538,37 @@ impl Sorn
 		Ok(())
 	}
 
+	/* Like set_bits, but bits beyond the last element are cleared instead of rejected */
+	pub fn set_bits_masked(&mut self, bits: SornBitsType)
+	{
+		self.bits = bits & self.valid_mask();
+	}
+
 	pub fn contains(&self, value: SornValue) -> bool
 	{
-		for (i, set) in self.sorn_set.borrow().sets.iter().enumerate()
-		{
-			if value == *set && (((1 << i) & self.bits) > 0)
-			{
-				return true;
-			}
-		}
+		return self.with_set(|set| set.sets.iter().enumerate().any(|(i, item)| value == *item && ((1 << i) & self.bits) > 0));
+	}
+
+	/*
+	True if every bucket of the set is active, so a completely unknown value can be told
+	apart from a wide one. Of the finite and [±inf] pairs only the undefined [±inf] * 0
+	saturates, finite + [±inf] and the other operations give just [±inf]. 0 / 0 saturates too.
+	*/
+	pub fn is_saturated(&self) -> bool
+	{
+		return self.bits != 0 && self.bits == self.valid_mask();
+	}
+
+	/* True if [±inf] or a tail reaching to infinity is active */
+	pub fn is_unbounded(&self) -> bool
+	{
+		return self.bits & self.with_set(|set| set.inf_bits()) != 0;
+	}
 
-		return false;
+	/* True if an active bucket contains exact, honoring open and closed ends, to check results against plain float math */
+	pub fn soundly_contains(&self, exact: Real) -> bool
+	{
+		return self.ranges_ref().iter().any(|range| range.contains_point(exact));
 	}
 
 	pub fn fit_contains(&self, value: SornValue) -> bool
@@ -137,1108 +597,3873 @@ impl Sorn
 		return valid_ranges;
 	}
 
-	pub fn get_min_range(&self) -> Option<SornValue>
+	/* The elements get_ranges leaves out, together both are the whole set */
+	pub fn inactive_ranges(&self) -> SornSet
 	{
-		if self.bits == 0
-		{
-			return None;
-		}
+		let mut complement = self.clone();
+		complement.bits = !self.bits & self.valid_mask();
 
-		return Some(self.get_ranges().get(0));
+		return complement.get_ranges();
 	}
 
-	pub fn get_max_range(&self) -> Option<SornValue>
+	/* Appends the active elements to out, so a loop can reuse one buffer instead of building a SornSet per call */
+	pub fn collect_ranges_into(&self, out: &mut Vec<SornValue>)
 	{
-		let ranges = self.get_ranges();
-
-		if ranges.len() == 0
-		{
-			return None;
-		}
+		out.extend(self.ranges_ref().iter().copied());
+	}
 
-		return Some(ranges.get(ranges.len()-1));
+	/* The bits next to the id of the set in a SornSetRegistry, see unpack */
+	pub fn pack(&self, set_id: u16) -> (u16, SornBitsType)
+	{
+		return (set_id, self.bits);
 	}
 
-	pub fn to_sornvalue(&self) -> SornValue
+	/* None if the id is unknown to registry or the bits don't fit its set */
+	pub fn unpack(registry: &SornSetRegistry, packed: (u16, SornBitsType)) -> Option<Sorn>
 	{
-		let ranges = self.get_ranges();
+		let (set_id, bits) = packed;
+		let mut sorn = Sorn::new(registry.get(set_id)?);
 
-		return ranges.get(0);
+		sorn.set_bits(bits).ok()?;
+
+		return Some(sorn);
 	}
 
-	pub fn sorn_to_bits(sorn_set: Rc<RefCell<SornSet>>, value: &SornValue) -> SornBitsType
+	/* Entry i is true if element i of the set is active */
+	pub fn to_mask(&self) -> Vec<bool>
 	{
-		let start = std::time::Instant::now();
-		let mut result = 0;
+		return self.enumerate_buckets().map(|(_, _, active)| active).collect();
+	}
 
-		for (i, item) in sorn_set.borrow().sets.iter().enumerate()
+	/* Number of active buckets */
+	pub fn cardinality(&self) -> usize
+	{
+		self.bits.count_ones() as usize
+	}
+
+	/* Buckets active in both Sorns */
+	pub fn intersect(&self, other: &Self) -> Result<Sorn, SornErrors>
+	{
+		if self.sorn_set != other.sorn_set
 		{
-			/* Check for Overlaps */
-			if (value.is_exact() && item.is_exact()) && (value.get().unwrap() == item.get().unwrap()) ||
-			(value.is_interval() && item.is_interval()) && (value.min() < item.max() && item.min() < value.max()) ||
-			(value.is_open() && item.is_exact()) && (value.min() < item.get().unwrap() && value.max() > item.get().unwrap()) ||
-			(value.is_exact() && item.is_open()) && (item.min() < value.get().unwrap() && item.max() > value.get().unwrap()) ||
-			(value.is_leftopen() && item.is_exact()) && (value.min() < item.get().unwrap() && value.max() >= item.get().unwrap()) ||
-			(value.is_exact() && item.is_leftopen()) && (item.min() < value.get().unwrap() && item.max() >= value.get().unwrap()) ||
-			(value.is_rightopen() && item.is_exact()) && (value.min() <= item.get().unwrap() && value.max() > item.get().unwrap()) ||
-			(value.is_exact() && item.is_rightopen()) && (item.min() <= value.get().unwrap() && item.max() > value.get().unwrap())
-			{
-				result |= 1 << i;
-			}
-			else if item.is_pminf() && value.is_pminf() && sorn_set.borrow().contains_inf
-			{
-				result |= 1 << 0;
-			}
+			return Err(SornErrors::DifferentSornSets);
 		}
 
-		return result;
+		let mut sorn = self.clone();
+		sorn.bits &= other.bits;
+
+		return Ok(sorn);
 	}
 
-	/* TODO only supports normal ranges and exacts */
-	pub fn pow(&mut self, power: i32) -> Sorn
+	/*
+	Buckets active in any of the Sorns, an empty Sorn without set if there are none.
+	Panics if the Sorns don't share a set.
+	*/
+	pub fn hull_of<I: IntoIterator<Item = Sorn>>(iter: I) -> Sorn
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
-		let mut result: SornBitsType = 0;
+		let mut iter = iter.into_iter();
+		let Some(mut hull) = iter.next() else { return Sorn::default(); };
 
-		if self.sorn_set.borrow().precomputed_pow.contains_key(&self.bits)
+		for sorn in iter
 		{
-			let result = 
-			{
-				let mut_set = self.sorn_set.borrow_mut();
-				*mut_set.precomputed_pow.get(&self.bits).unwrap()
-			};
-			let _ = sorn.set_bits(result);
-			return sorn;
+			assert!(hull.sorn_set == sorn.sorn_set, "Tried to build the hull of Sorns with different sets");
+			hull.bits |= sorn.bits;
 		}
-	
-		for val in &self.get_ranges().sets 
+
+		return hull;
+	}
+
+	/* Buckets active in either Sorn */
+	pub fn union(&self, other: &Self) -> Result<Sorn, SornErrors>
+	{
+		if self.sorn_set != other.sorn_set
+		{
+			return Err(SornErrors::DifferentSornSets);
+		}
+
+		let mut sorn = self.clone();
+		sorn.bits |= other.bits;
+
+		return Ok(sorn);
+	}
+
+	pub fn active_indices(&self) -> Vec<usize>
+	{
+		(0..self.with_set(SornSet::len)).filter(|i| self.bits & (1 << i) != 0).collect()
+	}
+
+	/*
+	Moves every active bucket to the next element of the set. mode decides what happens
+	to a bucket shifted past the last element.
+	*/
+	pub fn shift_up(&self, mode: ShiftMode) -> Sorn
+	{
+		let mask = self.valid_mask();
+		let top = mask & !(mask >> 1);
+
+		let mut sorn = self.clone();
+		sorn.bits = (self.bits << 1) & mask;
+
+		if self.bits & top != 0
 		{
-			let new_val = match val 
+			match mode
 			{
-				SornValue::Exact(v) => 
-				{
-					SornValue::Exact(v.powi(power))
-				}
-	
-				SornValue::Open((start, end)) => 
-				{
-					let a = start.powi(power);
-					let b = end.powi(power);
-
-					if a > b 
-					{
-						SornValue::Open((b, a))
-					}
-					else
-					{
-						SornValue::Open((a, b))
-					}
-				}
-	
-				SornValue::OpenLeft((start, end)) => 
-				{
-					let a = start.powi(power);
-					let b = end.powi(power);
-
-					if a > b 
-					{
-						SornValue::OpenRight((b, a))
-					}
-					else
-					{
-						SornValue::OpenLeft((a, b))
-					}
-				}
-	
-				SornValue::OpenRight((start, end)) => 
-				{
-					let a = start.powi(power);
-					let b = end.powi(power);
-
-					if a > b 
-					{
-						SornValue::OpenLeft((b, a))
-					}
-					else
-					{
-						SornValue::OpenRight((a, b))
-					}
-				}
-	
-				SornValue::PlusMinusInf => 
-				{
-					SornValue::PlusMinusInf
-				}
-	
-				SornValue::Empty => SornValue::Empty,
-			};
-	
-			result |= Self::sorn_to_bits(self.sorn_set.clone(), &new_val);
+				ShiftMode::Drop => {},
+				ShiftMode::Saturate => sorn.bits |= top,
+				ShiftMode::Wrap => sorn.bits |= 1,
+			}
 		}
 
-		self.sorn_set.borrow_mut().precomputed_pow.insert(self.bits, result);
-	
-		let _ = sorn.set_bits(result);
-		sorn
-	}	
+		return sorn;
+	}
 
-	pub fn abs(&mut self) -> Sorn
+	/* Like shift_up towards the first element of the set */
+	pub fn shift_down(&self, mode: ShiftMode) -> Sorn
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
-		let mut result: SornBitsType = 0;
+		let mask = self.valid_mask();
+		let top = mask & !(mask >> 1);
 
-		for val in &self.get_ranges().sets 
+		let mut sorn = self.clone();
+		sorn.bits = self.bits >> 1;
+
+		if self.bits & 1 != 0
 		{
-			let new_val = match val 
+			match mode
 			{
-				SornValue::Exact(v) => 
-				{
-					SornValue::Exact(v.abs())
-				}
-	
-				SornValue::Open((start, end)) => 
-				{
-					let a = start.abs();
-					let b = end.abs();
-
-					if a > b 
-					{
-						SornValue::Open((b, a))
-					}
-					else
-					{
-						SornValue::Open((a, b))
-					}
-				}
-	
-				SornValue::OpenLeft((start, end)) => 
-				{
-					let a = start.abs();
-					let b = end.abs();
-
-					if a > b 
-					{
-						SornValue::OpenRight((b, a))
-					}
-					else
-					{
-						SornValue::OpenLeft((a, b))
-					}
-				}
-	
-				SornValue::OpenRight((start, end)) => 
-				{
-					let a = start.abs();
-					let b = end.abs();
-
-					if a > b 
-					{
-						SornValue::OpenLeft((b, a))
-					}
-					else
-					{
-						SornValue::OpenRight((a, b))
-					}
-				}
-	
-				SornValue::PlusMinusInf => 
-				{
-					SornValue::PlusMinusInf
-				}
-	
-				SornValue::Empty => SornValue::Empty,
-			};
-	
-			result |= Self::sorn_to_bits(self.sorn_set.clone(), &new_val);
+				ShiftMode::Drop => {},
+				ShiftMode::Saturate => sorn.bits |= 1,
+				ShiftMode::Wrap => sorn.bits |= top,
+			}
 		}
 
-		let _ = sorn.set_bits(result);
-		sorn
+		return sorn;
 	}
 
-	pub fn negate(&mut self) -> Sorn
+	/*
+	Splits the active buckets into (below, at_or_above) threshold. A bucket that
+	straddles threshold is in both halves, [±inf] as well.
+	*/
+	pub fn split_at(&self, threshold: Real) -> (Sorn, Sorn)
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
-		let mut result: SornBitsType = 0;
+		let mut below = self.clone();
+		let mut above = self.clone();
+		below.bits = 0;
+		above.bits = 0;
 
-		for val in &self.get_ranges().sets 
+		for (i, value, active) in self.enumerate_buckets()
 		{
-			let new_val = match val 
+			if !active
 			{
-				SornValue::Exact(v) => 
-				{
-					SornValue::Exact(-v)
-				}
-	
-				SornValue::Open((start, end)) => 
-				{
-					let a = -start;
-					let b = -end;
-
-					if a > b 
-					{
-						SornValue::Open((b, a))
-					}
-					else
-					{
-						SornValue::Open((a, b))
-					}
-				}
-	
-				SornValue::OpenLeft((start, end)) => 
-				{
-					let a = -start;
-					let b = -end;
-
-					if a > b 
-					{
-						SornValue::OpenRight((b, a))
-					}
-					else
-					{
-						SornValue::OpenLeft((a, b))
-					}
-				}
-	
-				SornValue::OpenRight((start, end)) => 
-				{
-					let a = -start;
-					let b = -end;
-
-					if a > b 
-					{
-						SornValue::OpenLeft((b, a))
-					}
-					else
-					{
-						SornValue::OpenRight((a, b))
-					}
-				}
-	
-				SornValue::PlusMinusInf => 
-				{
-					SornValue::PlusMinusInf
-				}
-	
-				SornValue::Empty => SornValue::Empty,
+				continue;
+			}
+
+			let (min, max, max_closed) = match value.closed_bounds()
+			{
+				Some((min, _, max, max_closed)) => (min, max, max_closed),
+				None => (Real::NEG_INFINITY, Real::INFINITY, true),
 			};
-	
-			result |= Self::sorn_to_bits(self.sorn_set.clone(), &new_val);
+
+			if min < threshold
+			{
+				below.bits |= 1 << i;
+			}
+
+			if max > threshold || (max == threshold && max_closed)
+			{
+				above.bits |= 1 << i;
+			}
 		}
 
-		let _ = sorn.set_bits(result);
-		sorn
+		return (below, above);
 	}
 
-	fn checked_op(&mut self, operand: &Self, operation: &str) -> Option<SornErrors>
+	/* Number of inactive runs between active buckets, 0 for a contiguous Sorn and 1 for a bimodal one */
+	pub fn gap_count(&self) -> usize
 	{
-		if self.sorn_set != operand.sorn_set
+		/* Every active run starts at a set bit whose lower neighbour is not set */
+		let runs = (self.bits & !(self.bits << 1)).count_ones() as usize;
+
+		return runs.saturating_sub(1);
+	}
+
+	/* True if the active buckets are one unbroken run of neighbouring elements, false if empty */
+	pub fn is_contiguous(&self) -> bool
+	{
+		return self.bits != 0 && self.gap_count() == 0;
+	}
+
+	/* Every element of the set as (index, value, active) */
+	pub fn enumerate_buckets(&self) -> impl Iterator<Item = (usize, SornValue, bool)> + '_
+	{
+		/* The values are copied so the iterator does not hold the RefCell borrow */
+		let sets = self.with_set(|set| set.sets.clone());
+
+		sets.into_iter().enumerate().map(|(i, value)| (i, value, self.bits & (1 << i) != 0))
+	}
+
+	/* Like get_ranges, but borrows the set instead of building a new one, the set can't be mutated while the view lives */
+	pub fn ranges_ref(&self) -> RangesView<'_>
+	{
+		RangesView
 		{
-			return Some(SornErrors::DifferentSornSets);
+			set: self.sorn_set.borrow(),
+			bits: self.bits,
 		}
+	}
 
-		let self_ranges = self.get_ranges();
-		let operand_ranges = operand.get_ranges();
+	pub fn get_min_range(&self) -> Option<SornValue>
+	{
+		return self.ranges_ref().get(0).copied();
+	}
 
-		let mut result: SornBitsType = 0;
+	pub fn get_max_range(&self) -> Option<SornValue>
+	{
+		return self.ranges_ref().iter().last().copied();
+	}
 
-		if operation == "add"
+	/* Only the first active range, use try_into_value to make sure nothing is dropped */
+	pub fn to_sornvalue(&self) -> SornValue
+	{
+		let ranges = self.get_ranges();
+
+		return ranges.get(0);
+	}
+
+	/* The active range if there is exactly one */
+	pub fn try_into_value(&self) -> Result<SornValue, SornErrors>
+	{
+		let ranges = self.ranges_ref();
+
+		if ranges.len() != 1
 		{
-			if self.sorn_set.borrow().precomputed_add.contains_key(&(self.bits, operand.bits))
-			{
-				let mut result: SornBitsType = 0;
-				{
-					let mut_set = self.sorn_set.borrow_mut();
-					result = *mut_set.precomputed_add.get(&(self.bits, operand.bits)).unwrap();
-				}
-	
-				let _ = self.set_bits(result);
-				return None;
-			}
+			return Err(SornErrors::NotSingleBucket);
 		}
 
-		if operation == "sub"
+		return ranges.get(0).copied().ok_or(SornErrors::NotSingleBucket);
+	}
+
+	/* 1 if every active range is strictly positive, -1 if strictly negative, 0 if exactly zero */
+	pub fn sign(&self) -> Option<i8>
+	{
+		let ranges = self.get_ranges();
+
+		if ranges.is_empty()
+		{
+			return None;
+		}
+
+		let mut positive = true;
+		let mut negative = true;
+		let mut zero = true;
+
+		for range in &ranges.sets
+		{
+			/* PlusMinusInf has no sign */
+			let (min, min_closed, max, max_closed) = range.closed_bounds()?;
+
+			/* An open end at zero does not contain zero */
+			positive &= min > 0.0 || (min == 0.0 && !min_closed);
+			negative &= max < 0.0 || (max == 0.0 && !max_closed);
+			zero &= *range == SornValue::Exact(0.0);
+		}
+
+		if positive
+		{
+			return Some(1);
+		}
+		else if negative
+		{
+			return Some(-1);
+		}
+		else if zero
+		{
+			return Some(0);
+		}
+
+		return None;
+	}
+
+	/* Average of the active bucket midpoints, every bucket is weighted equally */
+	pub fn expected_value(&self) -> Option<Real>
+	{
+		let ranges = self.get_ranges();
+
+		if ranges.is_empty()
+		{
+			return None;
+		}
+
+		let mut sum = 0.0;
+
+		for range in &ranges.sets
 		{
-			if self.sorn_set.borrow().precomputed_sub.contains_key(&(self.bits, operand.bits))
+			if range.is_pminf() || range.min().is_infinite() || range.max().is_infinite()
 			{
-				let mut result: SornBitsType = 0;
-				{
-					let mut_set = self.sorn_set.borrow_mut();
-					result = *mut_set.precomputed_sub.get(&(self.bits, operand.bits)).unwrap();
-				}
-	
-				let _ = self.set_bits(result);
 				return None;
 			}
+
+			sum += (range.min() + range.max()) / 2.0;
 		}
 
-		if operation == "mul"
+		return Some(sum / ranges.len() as Real);
+	}
+
+	pub fn sorn_to_bits(sorn_set: Rc<RefCell<SornSet>>, value: &SornValue) -> SornBitsType
+	{
+		let mut result = 0;
+
+		for (i, item) in sorn_set.borrow().sets.iter().enumerate()
 		{
-			if self.sorn_set.borrow().precomputed_mul.contains_key(&(self.bits, operand.bits))
+			if item.overlaps(value)
 			{
-				let mut result: SornBitsType = 0;
-				{
-					let mut_set = self.sorn_set.borrow_mut();
-					result = *mut_set.precomputed_mul.get(&(self.bits, operand.bits)).unwrap();
-				}
-	
-				let _ = self.set_bits(result);
-				return None;
+				result |= 1 << i;
 			}
 		}
 
-		if operation == "div"
+		return result;
+	}
+
+	/*
+	Projects this Sorn onto another set. A target bucket is set as soon as it shares
+	a single real point with an active source bucket, including buckets that only
+	touch a closed endpoint, so the result never under-approximates the source.
+	*/
+	pub fn resample_conservative(&self, target: Rc<RefCell<SornSet>>) -> Sorn
+	{
+		let mut sorn = Sorn::new(target.clone());
+		let mut result: SornBitsType = 0;
+
+		for source in &self.get_ranges().sets
 		{
-			if self.sorn_set.borrow().precomputed_div.contains_key(&(self.bits, operand.bits))
+			for (i, item) in target.borrow().sets.iter().enumerate()
 			{
-				let mut result: SornBitsType = 0;
+				if source.overlaps(item)
 				{
-					let mut_set = self.sorn_set.borrow_mut();
-					result = *mut_set.precomputed_div.get(&(self.bits, operand.bits)).unwrap();
+					result |= 1 << i;
 				}
-	
-				let _ = self.set_bits(result);
-				return None;
 			}
 		}
 
-		/* Handle plus minus inf special case */
-		if self.contains(SornValue::PlusMinusInf) && operand.contains(SornValue::PlusMinusInf) 
-		{
-			let _ = self.set_bits(!0);
-		}
-		else if self.contains(SornValue::PlusMinusInf) || operand.contains(SornValue::PlusMinusInf) 
+		let _ = sorn.set_bits(result);
+		sorn
+	}
+
+	/*
+	A finite endpoint that overflows to inf is saturated to Real::MAX instead,
+	so it still lands in the infinite tail bucket of sets with contains_inf.
+	*/
+	fn pow_value(val: &SornValue, power: i32) -> SornValue
+	{
+		let powi = |x: Real|
 		{
-			let mut result = 0;
-			for i in 0..self.sorn_set.borrow().len()
+			let y = x.powi(power);
+
+			/* 0 to a negative power is the pole, not an overflow */
+			if y.is_infinite() && x.is_finite() && x != 0.0
 			{
-				result |= 1 << i;
+				return Real::MAX.copysign(y);
 			}
 
-			let _ = self.set_bits(result);
-		}
+			return y;
+		};
 
-		/* Handle normal cases */
-		for sorn1 in &self_ranges.sets
+		match val 
 		{
-			for sorn2 in &operand_ranges.sets
+			SornValue::Exact(v) if *v == 0.0 && power < 0 =>
 			{
-				let (a, b) = match operation
-				{
-					"add" => 
-					{
-						let a = sorn1.min() + sorn2.min();
-						let b = sorn1.min() + sorn2.max();
-						let c = sorn1.max() + sorn2.min();
-						let d = sorn1.max() + sorn2.max();
-						let min = f64::min(f64::min(a, b), f64::min(c, d));
-						let max = f64::max(f64::max(a, b), f64::max(c, d));
-
-						(min, max)
-					}
-					"sub" =>
-					{
-						let a = sorn1.min() - sorn2.min();
-						let b = sorn1.min() - sorn2.max();
-						let c = sorn1.max() - sorn2.min();
-						let d = sorn1.max() - sorn2.max();
-						let min = f64::min(f64::min(a, b), f64::min(c, d));
-						let max = f64::max(f64::max(a, b), f64::max(c, d));
-
-						(min, max)
-					}
-					"mul" => 
-					{
-						let a = sorn1.min() * sorn2.min();
-						let b = sorn1.min() * sorn2.max();
-						let c = sorn1.max() * sorn2.min();
-						let d = sorn1.max() * sorn2.max();
-						let min = f64::min(f64::min(a, b), f64::min(c, d));
-						let max = f64::max(f64::max(a, b), f64::max(c, d));
-
-						(min, max)
-					}
-					"div" => 
-					{
-						let a = sorn1.min() / sorn2.min();
-						let b = sorn1.min() / sorn2.max();
-						let c = sorn1.max() / sorn2.min();
-						let d = sorn1.max() / sorn2.max();
-						let min = f64::min(f64::min(a, b), f64::min(c, d));
-						let max = f64::max(f64::max(a, b), f64::max(c, d));
-
-						(min, max)
-					}
-					_ => (0.0, 0.0)
-				};
+				SornValue::PlusMinusInf
+			}
 
-				/* Exact numbers always equal exact ones */
-				if (sorn1.is_exact() && sorn2.is_exact()) || (operation == "mul" && (a, b) == (0.0, 0.0))
+			SornValue::Exact(v) => 
+			{
+				SornValue::Exact(powi(*v))
+			}
+
+			SornValue::Open((start, end)) => 
+			{
+				let a = powi(*start);
+				let b = powi(*end);
+
+				if a > b 
 				{
-					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::Exact(a));
+					SornValue::Open((b, a))
 				}
-				/* If one of the Numbers is Open, both become open after any operation */
-				else if sorn1.is_open() || sorn2.is_open()
+				else
 				{
-					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::Open((a, b)));
+					SornValue::Open((a, b))
 				}
-				/* If both are leftopen or one exact and the other leftopen its always results in OpenLeft */
-				else if (sorn1.is_leftopen() && sorn2.is_leftopen()) || (sorn1.is_leftopen() && sorn2.is_exact())
-				|| (sorn1.is_exact() && sorn2.is_leftopen())
+			}
+
+			SornValue::OpenLeft((start, end)) => 
+			{
+				let a = powi(*start);
+				let b = powi(*end);
+
+				if a > b 
 				{
-					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::OpenLeft((a, b)));
+					SornValue::OpenRight((b, a))
 				}
-				/* If both are rightopen or one exact and the other rightopen its always results in OpenRight */
-				else if (sorn1.is_rightopen() && sorn2.is_rightopen()) || (sorn1.is_rightopen() && sorn2.is_exact())
-				|| (sorn1.is_exact() && sorn2.is_rightopen())
+				else
 				{
-					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::OpenRight((a, b)));
+					SornValue::OpenLeft((a, b))
 				}
-				/* If they have opposite open directions then the result becomes Open */
-				else if (sorn1.is_leftopen() && sorn2.is_rightopen()) || (sorn1.is_rightopen() && sorn2.is_leftopen())
+			}
+
+			SornValue::OpenRight((start, end)) => 
+			{
+				let a = powi(*start);
+				let b = powi(*end);
+
+				if a > b 
 				{
-					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::Open((a, b)));
+					SornValue::OpenLeft((b, a))
 				}
-				else if (a == INFINITY && b == INFINITY) || (a == NEG_INFINITY && b == NEG_INFINITY)
+				else
 				{
-					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::PlusMinusInf);
+					SornValue::OpenRight((a, b))
 				}
 			}
-		}
 
-		if operation == "add"
-		{
-			self.sorn_set.borrow_mut().precomputed_add.insert((self.bits, operand.bits), result);
-			self.sorn_set.borrow_mut().precomputed_add.insert((operand.bits, self.bits), result);
+			SornValue::PlusMinusInf => 
+			{
+				SornValue::PlusMinusInf
+			}
+
+			SornValue::Empty => SornValue::Empty,
 		}
+	}
 
-		if operation == "sub"
+	/*
+	Could both Sorns hold the same value, even if they use different sets.
+	other is resampled onto the set of self first, which over-approximates,
+	so a true result only means an overlap is possible.
+	*/
+	pub fn overlaps_across_sets(&self, other: &Sorn) -> bool
+	{
+		let resampled = other.resample_conservative(self.sorn_set.clone());
+
+		(self.bits & resampled.bits) != 0
+	}
+
+	/* Number of buckets active in only one of both Sorns, 0 once an iteration has converged */
+	pub fn hamming_distance(&self, other: &Self) -> Option<u32>
+	{
+		if self.sorn_set != other.sorn_set
+		{
+			return None;
+		}
+
+		return Some((self.bits ^ other.bits).count_ones());
+	}
+
+	/* True if at most bucket_tolerance buckets differ, false for different sets */
+	pub fn approx_eq(&self, other: &Self, bucket_tolerance: u32) -> bool
+	{
+		return self.hamming_distance(other).is_some_and(|distance| distance <= bucket_tolerance);
+	}
+
+	/* True if no bucket is active in both Sorns, None if they use different sets */
+	pub fn is_disjoint(&self, other: &Self) -> Option<bool>
+	{
+		if self.sorn_set != other.sorn_set
+		{
+			return None;
+		}
+
+		return Some(self.bits & other.bits == 0);
+	}
+
+	/* (min, min_closed, max, max_closed) over all active buckets, None if empty or [±inf] is active */
+	fn hull_bounds(&self) -> Option<(Real, bool, Real, bool)>
+	{
+		let ranges = self.ranges_ref();
+		let mut hull: Option<(Real, bool, Real, bool)> = None;
+
+		for item in ranges.iter()
+		{
+			let (min, min_closed, max, max_closed) = item.closed_bounds()?;
+
+			hull = Some(match hull
+			{
+				None => (min, min_closed, max, max_closed),
+				Some((h_min, h_min_closed, h_max, h_max_closed)) =>
+				{
+					let (min, min_closed) = if min < h_min { (min, min_closed) }
+						else if min == h_min { (min, min_closed || h_min_closed) }
+						else { (h_min, h_min_closed) };
+
+					let (max, max_closed) = if max > h_max { (max, max_closed) }
+						else if max == h_max { (max, max_closed || h_max_closed) }
+						else { (h_max, h_max_closed) };
+
+					(min, min_closed, max, max_closed)
+				},
+			});
+		}
+
+		return hull;
+	}
+
+	fn hull_width(&self) -> Real
+	{
+		if self.bits == 0
+		{
+			return 0.0;
+		}
+
+		return match self.hull_bounds()
+		{
+			Some((min, _, max, _)) => max - min,
+			None => Real::INFINITY,
+		};
+	}
+
+	/*
+	The closest exact points of the set at or below the lowest value and at or above the
+	highest value, None if the Sorn is empty, unbounded or not enclosed by exact points
+	*/
+	pub fn enclosing_exacts(&self) -> Option<(Real, Real)>
+	{
+		let (min, _, max, _) = self.hull_bounds()?;
+
+		if !min.is_finite() || !max.is_finite()
+		{
+			return None;
+		}
+
+		return self.with_set(|set| Some((set.floor_exact(min)?, set.ceil_exact(max)?)));
+	}
+
+	/*
+	Whether every value of self is below every value of other, False if none is,
+	Unknown otherwise. The bounds are compared, so the Sorns may use different sets.
+	*/
+	pub fn lt(&self, other: &Self) -> Trilean
+	{
+		let (Some((a_min, _, a_max, a_max_closed)), Some((b_min, b_min_closed, b_max, _))) = (self.hull_bounds(), other.hull_bounds())
+		else { return Trilean::Unknown; };
+
+		if a_max < b_min || (a_max == b_min && !(a_max_closed && b_min_closed))
+		{
+			return Trilean::True;
+		}
+
+		if a_min >= b_max
+		{
+			return Trilean::False;
+		}
+
+		return Trilean::Unknown;
+	}
+
+	pub fn le(&self, other: &Self) -> Trilean
+	{
+		return match other.lt(self)
+		{
+			Trilean::True => Trilean::False,
+			Trilean::False => Trilean::True,
+			Trilean::Unknown => Trilean::Unknown,
+		};
+	}
+
+	pub fn gt(&self, other: &Self) -> Trilean
+	{
+		return other.lt(self);
+	}
+
+	pub fn ge(&self, other: &Self) -> Trilean
+	{
+		return other.le(self);
+	}
+
+	/* True only if both are the same exact value, False if no bucket of self overlaps one of other */
+	pub fn eq_interval(&self, other: &Self) -> Trilean
+	{
+		if self.bits == 0 || other.bits == 0
+		{
+			return Trilean::Unknown;
+		}
+
+		let single = |hull: Option<(Real, bool, Real, bool)>| hull.filter(|(min, _, max, _)| min == max).map(|(min, ..)| min);
+
+		if let (Some(a), Some(b)) = (single(self.hull_bounds()), single(other.hull_bounds()))
+		{
+			return if a == b { Trilean::True } else { Trilean::False };
+		}
+
+		return self.overlap_trilean(other);
+	}
+
+	fn overlap_trilean(&self, other: &Self) -> Trilean
+	{
+		let a_ranges = self.ranges_ref();
+		let b_ranges = other.ranges_ref();
+
+		if a_ranges.iter().any(|a| b_ranges.iter().any(|b| a.overlaps(b)))
+		{
+			return Trilean::Unknown;
+		}
+
+		return Trilean::False;
+	}
+
+	/*
+	Applies f until the bits stop changing or max_iters is reached, returning the last
+	result and the number of applications. A result equal to the one two steps back is
+	an oscillation and also stops the iteration.
+	*/
+	pub fn iterate<F: Fn(&Sorn) -> Sorn>(&self, f: F, max_iters: usize) -> (Sorn, usize)
+	{
+		let mut current = self.clone();
+		let mut previous: Option<SornBitsType> = None;
+
+		for i in 0..max_iters
+		{
+			let next = f(&current);
+
+			if next.hamming_distance(&current) == Some(0) || previous == Some(next.bits)
+			{
+				return (next, i + 1);
+			}
+
+			previous = Some(current.bits);
+			current = next;
+		}
+
+		return (current, max_iters);
+	}
+
+	/* TODO only supports normal ranges and exacts */
+	pub fn pow(&mut self, power: i32) -> Sorn
+	{
+		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let mut result: SornBitsType = 0;
+
+		let cached = self.sorn_set.borrow_mut().precomputed_pow.get(&(self.bits, power));
+		if let Some(result) = cached
+		{
+			let _ = sorn.set_bits(result);
+			return sorn;
+		}
+	
+		for val in &self.get_ranges().sets 
+		{
+			let new_val = Self::pow_value(val, power);
+	
+			result |= Self::sorn_to_bits(self.sorn_set.clone(), &new_val);
+		}
+
+		self.sorn_set.borrow_mut().precomputed_pow.insert((self.bits, power), result);
+	
+		let _ = sorn.set_bits(result);
+		sorn
+	}	
+
+	/* Like pow, but fails if any active range is mapped outside of the set instead of dropping it */
+	pub fn checked_pow(&mut self, power: i32) -> Result<Sorn, SornErrors>
+	{
+		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let mut result: SornBitsType = 0;
+
+		for val in &self.get_ranges().sets
+		{
+			let bits = Self::sorn_to_bits(self.sorn_set.clone(), &Self::pow_value(val, power));
+
+			if bits == 0
+			{
+				return Err(SornErrors::NotInRange);
+			}
+
+			result |= bits;
+		}
+
+		sorn.set_bits(result)?;
+		Ok(sorn)
+	}
+
+	fn abs_value(val: &SornValue) -> SornValue
+	{
+		match val 
+		{
+			SornValue::Exact(v) => 
+			{
+				SornValue::Exact(v.abs())
+			}
+
+			SornValue::Open((start, end)) => 
+			{
+				let a = start.abs();
+				let b = end.abs();
+
+				if a > b 
+				{
+					SornValue::Open((b, a))
+				}
+				else
+				{
+					SornValue::Open((a, b))
+				}
+			}
+
+			SornValue::OpenLeft((start, end)) => 
+			{
+				let a = start.abs();
+				let b = end.abs();
+
+				if a > b 
+				{
+					SornValue::OpenRight((b, a))
+				}
+				else
+				{
+					SornValue::OpenLeft((a, b))
+				}
+			}
+
+			SornValue::OpenRight((start, end)) => 
+			{
+				let a = start.abs();
+				let b = end.abs();
+
+				if a > b 
+				{
+					SornValue::OpenLeft((b, a))
+				}
+				else
+				{
+					SornValue::OpenRight((a, b))
+				}
+			}
+
+			SornValue::PlusMinusInf => 
+			{
+				SornValue::PlusMinusInf
+			}
+
+			SornValue::Empty => SornValue::Empty,
+		}
+	}
+
+	pub fn abs(&mut self) -> Sorn
+	{
+		return self.map_ranges(false, |set, val| Self::sorn_to_bits(set, &Self::abs_value(val))).unwrap();
+	}
+
+	pub fn checked_abs(&mut self) -> Result<Sorn, SornErrors>
+	{
+		return self.map_ranges(true, |set, val| Self::sorn_to_bits(set, &Self::abs_value(val)));
+	}
+
+	/*
+	|self| + |other| in one pass over the bucket pairs. The absolute values are added before
+	they are projected onto the set, so this can be tighter than abs, abs and add.
+	*/
+	pub fn abs_add(&self, other: &Self) -> Result<Sorn, SornErrors>
+	{
+		if self.sorn_set != other.sorn_set
+		{
+			return Err(SornErrors::DifferentSornSets);
+		}
+
+		let mut result = Sorn::new(self.sorn_set.clone());
+
+		for sorn1 in self.ranges_ref().iter().map(Self::abs_value)
+		{
+			for sorn2 in other.ranges_ref().iter().map(Self::abs_value)
+			{
+				if let Some(bits) = Self::inf_op_bits(self.sorn_set.clone(), &sorn1, &sorn2, "add")
+				{
+					result.bits |= bits;
+				}
+				else if let Some((min, min_closed, max, max_closed)) = Self::add_sub_bounds(&sorn1, &sorn2, "add")
+				{
+					result.bits |= Self::interval_to_bits(self.sorn_set.clone(), min, max, !min_closed, !max_closed);
+				}
+			}
+		}
+
+		return Ok(result);
+	}
+
+	fn negate_value(val: &SornValue) -> SornValue
+	{
+		match val 
+		{
+			SornValue::Exact(v) => 
+			{
+				SornValue::Exact(-v)
+			}
+
+			SornValue::Open((start, end)) => 
+			{
+				let a = -start;
+				let b = -end;
+
+				if a > b 
+				{
+					SornValue::Open((b, a))
+				}
+				else
+				{
+					SornValue::Open((a, b))
+				}
+			}
+
+			SornValue::OpenLeft((start, end)) => 
+			{
+				let a = -start;
+				let b = -end;
+
+				if a > b 
+				{
+					SornValue::OpenRight((b, a))
+				}
+				else
+				{
+					SornValue::OpenLeft((a, b))
+				}
+			}
+
+			SornValue::OpenRight((start, end)) => 
+			{
+				let a = -start;
+				let b = -end;
+
+				if a > b 
+				{
+					SornValue::OpenLeft((b, a))
+				}
+				else
+				{
+					SornValue::OpenRight((a, b))
+				}
+			}
+
+			SornValue::PlusMinusInf => 
+			{
+				SornValue::PlusMinusInf
+			}
+
+			SornValue::Empty => SornValue::Empty,
+		}
+	}
+
+	pub fn negate(&mut self) -> Sorn
+	{
+		return self.map_ranges(false, |set, val| Self::sorn_to_bits(set, &Self::negate_value(val))).unwrap();
+	}
+
+	/* Fails with NotInRange if a negated range has no counterpart in the set, e.g. on asymmetric sets */
+	pub fn checked_negate(&mut self) -> Result<Sorn, SornErrors>
+	{
+		return self.map_ranges(true, |set, val| Self::sorn_to_bits(set, &Self::negate_value(val)));
+	}
+
+	/* Negative parts of a range have no real root and are dropped */
+	fn sqrt_bits(set: Rc<RefCell<SornSet>>, val: &SornValue) -> SornBitsType
+	{
+		if val.is_pminf()
+		{
+			return Self::sorn_to_bits(set, val);
+		}
+
+		let Some((lo, lo_closed, hi, hi_closed)) = val.closed_bounds() else { return 0; };
+
+		if hi < 0.0 || (hi == 0.0 && !hi_closed)
+		{
+			return 0;
+		}
+
+		let (lo, lo_closed) = if lo < 0.0 { (0.0, true) } else { (lo.sqrt(), lo_closed) };
+
+		return Self::interval_to_bits(set, lo, hi.sqrt(), !lo_closed, !hi_closed);
+	}
+
+	pub fn sqrt(&mut self) -> Sorn
+	{
+		return self.map_ranges(false, Self::sqrt_bits).unwrap();
+	}
+
+	/* Fails with NotInRange if a range is entirely negative or its root is outside of the set */
+	pub fn checked_sqrt(&mut self) -> Result<Sorn, SornErrors>
+	{
+		return self.map_ranges(true, Self::sqrt_bits);
+	}
+
+	/*
+	val split at 0 into its negative part, 0 itself and its positive part. x^power is monotone
+	on both parts, so their endpoints give the bounds. Fractional powers only see the positive part.
+	*/
+	fn powf_bits(set: Rc<RefCell<SornSet>>, val: &SornValue, power: Real) -> SornBitsType
+	{
+		if power == 0.0
+		{
+			return if *val == SornValue::Empty { 0 } else { Self::sorn_to_bits(set, &SornValue::Exact(1.0)) };
+		}
+
+		if val.is_pminf()
+		{
+			let image = if power > 0.0 { SornValue::PlusMinusInf } else { SornValue::Exact(0.0) };
+			return Self::sorn_to_bits(set, &image);
+		}
+
+		let Some((a, a_closed, b, b_closed)) = val.closed_bounds() else { return 0; };
+
+		let mut result = 0;
+		let mut image = |lo: Real, lo_closed: bool, hi: Real, hi_closed: bool|
+		{
+			let (flo, fhi) = (lo.powf(power), hi.powf(power));
+
+			result |= if flo <= fhi
+			{
+				Self::interval_to_bits(set.clone(), flo, fhi, !lo_closed, !hi_closed)
+			}
+			else
+			{
+				Self::interval_to_bits(set.clone(), fhi, flo, !hi_closed, !lo_closed)
+			};
+		};
+
+		if a < 0.0 && power.fract() == 0.0
+		{
+			/* The open end at -0 gives the sign of the pole for negative powers */
+			if b < 0.0 { image(a, a_closed, b, b_closed) } else { image(a, a_closed, -0.0, false) }
+		}
+
+		if b > 0.0
+		{
+			if a > 0.0 { image(a, a_closed, b, b_closed) } else { image(0.0, false, b, b_closed) }
+		}
+
+		if val.contains_point(0.0)
+		{
+			let image = if power > 0.0 { SornValue::Exact(0.0) } else { SornValue::PlusMinusInf };
+			result |= Self::sorn_to_bits(set, &image);
+		}
+
+		return result;
+	}
+
+	/*
+	x^power for any real power. Negative powers map 0 onto [±inf], fractional powers
+	are only defined for x >= 0 and drop the negative part of a range.
+	*/
+	pub fn powf(&mut self, power: Real) -> Sorn
+	{
+		return self.map_ranges(false, |set, val| Self::powf_bits(set, val, power)).unwrap();
+	}
+
+	/* 1 / x, same as powf(-1.0) */
+	pub fn reciprocal(&mut self) -> Sorn
+	{
+		return self.powf(-1.0);
+	}
+
+	/* Reduces val modulo period onto [0, period), a range crossing a multiple of period is split in two */
+	fn wrap_angle_bits(set: Rc<RefCell<SornSet>>, val: &SornValue, period: Real) -> SornBitsType
+	{
+		let Some((a, a_closed, b, b_closed)) = val.closed_bounds() else { return 0; };
+
+		if b - a >= period
+		{
+			return Self::interval_to_bits(set, 0.0, period, false, true);
+		}
+
+		let shift = (a / period).floor() * period;
+		let (a, b) = (a - shift, b - shift);
+
+		if b < period
+		{
+			return Self::interval_to_bits(set, a, b, !a_closed, !b_closed);
+		}
+
+		return Self::interval_to_bits(set.clone(), a, period, !a_closed, true)
+			| Self::interval_to_bits(set, 0.0, b - period, false, !b_closed);
+	}
+
+	/*
+	Every active range reduced onto [0, period), ranges at least one period wide cover all of it.
+	[±inf] has no angle and is dropped.
+	*/
+	pub fn wrap_angle(&mut self, period: Real) -> Sorn
+	{
+		return self.map_ranges(false, |set, val| Self::wrap_angle_bits(set, val, period)).unwrap();
+	}
+
+	/*
+	Image of val under a 2pi periodic f with its maxima at max_at + 2kpi and minima at min_at + 2kpi.
+	The endpoints give the bounds unless val contains an extremum, then that bound is the closed 1 or -1.
+	*/
+	fn periodic_bits(set: Rc<RefCell<SornSet>>, val: &SornValue, f: fn(Real) -> Real, max_at: Real, min_at: Real) -> SornBitsType
+	{
+		const TAU: Real = std::f64::consts::TAU as Real;
+
+		let Some((a, a_closed, b, b_closed)) = val.closed_bounds() else { return 0; };
+
+		if b - a >= TAU
+		{
+			return Self::interval_to_bits(set, -1.0, 1.0, false, false);
+		}
+
+		let (fa, fb) = (f(a), f(b));
+		let (mut lo, mut lo_closed) = if fa < fb { (fa, a_closed) } else if fb < fa { (fb, b_closed) } else { (fa, a_closed || b_closed) };
+		let (mut hi, mut hi_closed) = if fa > fb { (fa, a_closed) } else if fb > fa { (fb, b_closed) } else { (fa, a_closed || b_closed) };
+
+		/* First extremum at or above a */
+		let first_after = |at: Real| at + ((a - at) / TAU).ceil() * TAU;
+
+		if val.contains_point(first_after(max_at))
+		{
+			(hi, hi_closed) = (1.0, true);
+		}
+
+		if val.contains_point(first_after(min_at))
+		{
+			(lo, lo_closed) = (-1.0, true);
+		}
+
+		return Self::interval_to_bits(set, lo, hi, !lo_closed, !hi_closed);
+	}
+
+	pub fn sin(&mut self) -> Sorn
+	{
+		const FRAC_PI_2: Real = std::f64::consts::FRAC_PI_2 as Real;
+
+		return self.map_ranges(false, |set, val| Self::periodic_bits(set, val, Real::sin, FRAC_PI_2, -FRAC_PI_2)).unwrap();
+	}
+
+	pub fn cos(&mut self) -> Sorn
+	{
+		const PI: Real = std::f64::consts::PI as Real;
+
+		return self.map_ranges(false, |set, val| Self::periodic_bits(set, val, Real::cos, 0.0, PI)).unwrap();
+	}
+
+	/*
+	ORs the bits f maps every active range to. With strict set, a range that maps
+	to no bucket at all fails with NotInRange instead of being dropped.
+	*/
+	fn map_ranges(&self, strict: bool, f: impl Fn(Rc<RefCell<SornSet>>, &SornValue) -> SornBitsType) -> Result<Sorn, SornErrors>
+	{
+		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let mut result: SornBitsType = 0;
+
+		for val in &self.get_ranges().sets
+		{
+			let bits = f(self.sorn_set.clone(), val);
+
+			if strict && bits == 0
+			{
+				return Err(SornErrors::NotInRange);
+			}
+
+			result |= bits;
+		}
+
+		sorn.set_bits(result)?;
+		Ok(sorn)
+	}
+
+	/* (min, min_closed, max, max_closed) of sorn1 + sorn2 or sorn1 - sorn2 */
+	fn add_sub_bounds(sorn1: &SornValue, sorn2: &SornValue, operation: &str) -> Option<(Real, bool, Real, bool)>
+	{
+		let (min1, min1_closed, max1, max1_closed) = sorn1.closed_bounds()?;
+		let (min2, min2_closed, max2, max2_closed) = sorn2.closed_bounds()?;
+
+		match operation
+		{
+			"add" => Some((min1 + min2, min1_closed && min2_closed, max1 + max2, max1_closed && max2_closed)),
+			"sub" => Some((min1 - max2, min1_closed && max2_closed, max1 - min2, max1_closed && min2_closed)),
+			_ => None,
+		}
+	}
+
+	/*
+	Result of a pair where at least one side is [±inf], None if neither is.
+	A finite value does not change ±inf, except that ±inf * 0 is undefined and
+	becomes every bucket of the set. x / ±inf is 0 for every finite x.
+	Two [±inf] stay [±inf] for every operation.
+	*/
+	fn inf_op_bits(set: Rc<RefCell<SornSet>>, sorn1: &SornValue, sorn2: &SornValue, operation: &str) -> Option<SornBitsType>
+	{
+		if !sorn1.is_pminf() && !sorn2.is_pminf()
+		{
+			return None;
+		}
+
+		let all = mask_for_len(set.borrow().len());
+		let inf = Self::sorn_to_bits(set.clone(), &SornValue::PlusMinusInf);
+		let zero = SornValue::Exact(0.0);
+
+		let bits = match operation
+		{
+			_ if sorn1.is_pminf() && sorn2.is_pminf() => inf,
+			"add" | "sub" => inf,
+			"mul" if sorn1.overlaps(&zero) || sorn2.overlaps(&zero) => all,
+			"mul" => inf,
+			"div" if sorn1.is_pminf() => inf,
+			"div" => Self::sorn_to_bits(set.clone(), &zero),
+			_ => 0,
+		};
+
+		return Some(bits);
+	}
+
+	/*
+	Result of sorn1 / sorn2 if the closure of sorn2 touches 0, None otherwise. Each side of 0
+	the divisor reaches is divided on its own, towards 0 the quotient grows without bound.
+	x / 0 is ±inf and 0 / 0 is undefined, that becomes every bucket of the set.
+	*/
+	fn div_zero_bits(set: Rc<RefCell<SornSet>>, sorn1: &SornValue, sorn2: &SornValue) -> Option<SornBitsType>
+	{
+		let (n0, n0_closed, n1, n1_closed) = sorn1.closed_bounds()?;
+		let (d0, d0_closed, d1, d1_closed) = sorn2.closed_bounds()?;
+
+		if d0 > 0.0 || d1 < 0.0
+		{
+			return None;
+		}
+
+		/* sorn1 / (0, magnitude] as (lo, lo_closed, hi, hi_closed), magnitude_closed tells if it is ] or ) */
+		let positive_side = |magnitude: Real, magnitude_closed: bool|
+		{
+			if n0 > 0.0 || (n0 == 0.0 && !n0_closed)
+			{
+				return (n0 / magnitude, n0_closed && magnitude_closed, Real::INFINITY, false);
+			}
+
+			if n1 < 0.0 || (n1 == 0.0 && !n1_closed)
+			{
+				return (Real::NEG_INFINITY, false, n1 / magnitude, n1_closed && magnitude_closed);
+			}
+
+			/* sorn1 holds 0 here, which stays 0 */
+			let (lo, lo_closed) = if n0 < 0.0 { (Real::NEG_INFINITY, false) } else { (0.0, true) };
+			let (hi, hi_closed) = if n1 > 0.0 { (Real::INFINITY, false) } else { (0.0, true) };
+
+			return (lo, lo_closed, hi, hi_closed);
+		};
+
+		let mut result = 0;
+
+		if d1 > 0.0
+		{
+			let (lo, lo_closed, hi, hi_closed) = positive_side(d1, d1_closed);
+			result |= Self::interval_to_bits(set.clone(), lo, hi, !lo_closed, !hi_closed);
+		}
+
+		/* sorn1 / [-magnitude, 0) is the mirror of sorn1 / (0, magnitude] */
+		if d0 < 0.0
+		{
+			let (lo, lo_closed, hi, hi_closed) = positive_side(-d0, d0_closed);
+			result |= Self::interval_to_bits(set.clone(), -hi, -lo, !hi_closed, !lo_closed);
+		}
+
+		if sorn2.contains_point(0.0)
+		{
+			result |= if sorn1.contains_point(0.0) { mask_for_len(set.borrow().len()) } else { Self::sorn_to_bits(set.clone(), &SornValue::PlusMinusInf) };
+		}
+
+		return Some(result);
+	}
+
+	/* Lowest and highest of the four endpoint combinations of sorn1 op sorn2 */
+	fn pair_bounds(sorn1: &SornValue, sorn2: &SornValue, operation: &str) -> (Real, Real)
+	{
+		match operation
+		{
+			"add" => 
+			{
+				let a = sorn1.min() + sorn2.min();
+				let b = sorn1.min() + sorn2.max();
+				let c = sorn1.max() + sorn2.min();
+				let d = sorn1.max() + sorn2.max();
+				let min = Real::min(Real::min(a, b), Real::min(c, d));
+				let max = Real::max(Real::max(a, b), Real::max(c, d));
+
+				(min, max)
+			}
+			"sub" =>
+			{
+				let a = sorn1.min() - sorn2.min();
+				let b = sorn1.min() - sorn2.max();
+				let c = sorn1.max() - sorn2.min();
+				let d = sorn1.max() - sorn2.max();
+				let min = Real::min(Real::min(a, b), Real::min(c, d));
+				let max = Real::max(Real::max(a, b), Real::max(c, d));
+
+				(min, max)
+			}
+			"mul" => 
+			{
+				let a = sorn1.min() * sorn2.min();
+				let b = sorn1.min() * sorn2.max();
+				let c = sorn1.max() * sorn2.min();
+				let d = sorn1.max() * sorn2.max();
+				let min = Real::min(Real::min(a, b), Real::min(c, d));
+				let max = Real::max(Real::max(a, b), Real::max(c, d));
+
+				(min, max)
+			}
+			"div" => 
+			{
+				let a = sorn1.min() / sorn2.min();
+				let b = sorn1.min() / sorn2.max();
+				let c = sorn1.max() / sorn2.min();
+				let d = sorn1.max() / sorn2.max();
+				let min = Real::min(Real::min(a, b), Real::min(c, d));
+				let max = Real::max(Real::max(a, b), Real::max(c, d));
+
+				(min, max)
+			}
+			_ => (0.0, 0.0)
+		}
+	}
+
+	fn checked_op(&mut self, operand: &Self, operation: &str) -> Option<SornErrors>
+	{
+		if self.sorn_set != operand.sorn_set
+		{
+			return Some(SornErrors::DifferentSornSets);
+		}
+
+		#[cfg(feature = "trace")]
+		{
+			self.provenance = Op::from_name(operation).map(|op| (op, self.bits, operand.bits));
+		}
+
+		let mut result: SornBitsType = 0;
+
+		let cached =
+		{
+			let mut set = self.sorn_set.borrow_mut();
+			let key = (self.bits, operand.bits);
+
+			match operation
+			{
+				"add" => set.precomputed_add.get(&key),
+				"sub" => set.precomputed_sub.get(&key),
+				"mul" => set.precomputed_mul.get(&key),
+				"div" => set.precomputed_div.get(&key),
+				_ => None,
+			}
+		};
+
+		if let Some(result) = cached
+		{
+			self.sorn_set.borrow_mut().record_cache_lookup(operation, true);
+
+			let _ = self.set_bits(result);
+			return None;
+		}
+
+		let table_result = Op::from_name(operation).and_then(|op| self.sorn_set.borrow().table_lookup(op, self.bits, operand.bits));
+		if let Some(result) = table_result
+		{
+			self.sorn_set.borrow_mut().record_table_hit(operation);
+
+			let _ = self.set_bits(result);
+			return None;
+		}
+
+		self.sorn_set.borrow_mut().record_cache_lookup(operation, false);
+
+		let mut self_ranges = Vec::new();
+		let mut operand_ranges = Vec::new();
+		self.collect_ranges_into(&mut self_ranges);
+		operand.collect_ranges_into(&mut operand_ranges);
+
+		/* Handle normal cases */
+		for sorn1 in &self_ranges
+		{
+			for sorn2 in &operand_ranges
+			{
+				if let Some(bits) = Self::inf_op_bits(self.sorn_set.clone(), sorn1, sorn2, operation)
+				{
+					result |= bits;
+					continue;
+				}
+
+				if operation == "div" && let Some(bits) = Self::div_zero_bits(self.sorn_set.clone(), sorn1, sorn2)
+				{
+					result |= bits;
+					continue;
+				}
+
+				/* Add and sub know which endpoints produce the bounds, so their closedness is tracked exactly */
+				if let Some((min, min_closed, max, max_closed)) = Self::add_sub_bounds(sorn1, sorn2, operation)
+				{
+					result |= Self::interval_to_bits(self.sorn_set.clone(), min, max, !min_closed, !max_closed);
+					continue;
+				}
+
+				let (a, b) = Self::pair_bounds(sorn1, sorn2, operation);
+
+				/* Exact numbers always equal exact ones */
+				if (sorn1.is_exact() && sorn2.is_exact()) || ((operation == "mul" || operation == "div") && (a, b) == (0.0, 0.0))
+				{
+					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::Exact(a));
+				}
+				/* If one of the Numbers is Open, both become open after any operation */
+				else if sorn1.is_open() || sorn2.is_open()
+				{
+					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::Open((a, b)));
+				}
+				/* If both are leftopen or one exact and the other leftopen its always results in OpenLeft */
+				else if (sorn1.is_leftopen() && sorn2.is_leftopen()) || (sorn1.is_leftopen() && sorn2.is_exact())
+				|| (sorn1.is_exact() && sorn2.is_leftopen())
+				{
+					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::OpenLeft((a, b)));
+				}
+				/* If both are rightopen or one exact and the other rightopen its always results in OpenRight */
+				else if (sorn1.is_rightopen() && sorn2.is_rightopen()) || (sorn1.is_rightopen() && sorn2.is_exact())
+				|| (sorn1.is_exact() && sorn2.is_rightopen())
+				{
+					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::OpenRight((a, b)));
+				}
+				/* If they have opposite open directions then the result becomes Open */
+				else if (sorn1.is_leftopen() && sorn2.is_rightopen()) || (sorn1.is_rightopen() && sorn2.is_leftopen())
+				{
+					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::Open((a, b)));
+				}
+				else if (a == Real::INFINITY && b == Real::INFINITY) || (a == Real::NEG_INFINITY && b == Real::NEG_INFINITY)
+				{
+					result |= Self::sorn_to_bits(self.sorn_set.clone(), &SornValue::PlusMinusInf);
+				}
+			}
+		}
+
+		if operation == "add"
+		{
+			self.sorn_set.borrow_mut().precomputed_add.insert((self.bits, operand.bits), result);
+			self.sorn_set.borrow_mut().precomputed_add.insert((operand.bits, self.bits), result);
+		}
+
+		if operation == "sub"
+		{
+			self.sorn_set.borrow_mut().precomputed_sub.insert((self.bits, operand.bits), result);
+		}
+
+		if operation == "mul"
+		{
+			self.sorn_set.borrow_mut().precomputed_mul.insert((self.bits, operand.bits), result);
+			self.sorn_set.borrow_mut().precomputed_mul.insert((operand.bits, self.bits), result);
+		}
+
+		if operation == "div"
+		{
+			self.sorn_set.borrow_mut().precomputed_div.insert((self.bits, operand.bits), result);
+		}
+		
+		let _ = self.set_bits(result);
+
+		return None;
+	}
+
+	/*
+	Number of (self, operand) bucket pairs checked_op combines for op. Every operation
+	visits the whole cross product, a footprint much larger than the cardinality of the
+	result means most of the precision is lost to the projection.
+	*/
+	pub fn checked_op_footprint(&self, operand: &Self, _op: Op) -> Result<usize, SornErrors>
+	{
+		if self.sorn_set != operand.sorn_set
+		{
+			return Err(SornErrors::DifferentSornSets);
+		}
+
+		return Ok(self.cardinality() * operand.cardinality());
+	}
+
+	/*
+	Bounds of self op operand over the hulls of both Sorns, before they are projected
+	onto the set. The width compared to the projected result shows the quantization loss.
+	A divisor whose hull touches 0 makes the quotient unbounded, that gives (-inf, inf).
+	*/
+	pub fn checked_op_exact(&self, operand: &Self, op: Op) -> Result<(Real, Real), SornErrors>
+	{
+		if self.sorn_set != operand.sorn_set
+		{
+			return Err(SornErrors::DifferentSornSets);
+		}
+
+		let hull = |sorn: &Sorn| match (sorn.get_min_range(), sorn.get_max_range())
+		{
+			(Some(min), Some(max)) => Ok(SornValue::Open((min.min(), max.max()))),
+			_ => Err(SornErrors::NotInRange),
+		};
+
+		let (lhs, rhs) = (hull(self)?, hull(operand)?);
+
+		if op == Op::Div && rhs.min() <= 0.0 && 0.0 <= rhs.max()
+		{
+			return Ok((Real::NEG_INFINITY, Real::INFINITY));
+		}
+
+		return Ok(Self::pair_bounds(&lhs, &rhs, op.name()));
+	}
+
+	/* checked_op that also reports which bucket pairs produced the bounds of the result */
+	pub fn checked_op_explained(&mut self, operand: &Self, op: Op) -> Result<OpResult, SornErrors>
+	{
+		let before = self.clone();
+
+		if let Some(err) = self.checked_op(operand, op.name())
+		{
+			return Err(err);
+		}
+
+		let mut min: Option<(Real, (usize, usize))> = None;
+		let mut max: Option<(Real, (usize, usize))> = None;
+
+		for i in before.active_indices()
+		{
+			for j in operand.active_indices()
+			{
+				let (sorn1, sorn2) = self.with_set(|set| (set.get(i), set.get(j)));
+
+				if sorn1.is_pminf() || sorn2.is_pminf()
+				{
+					continue;
+				}
+
+				let (lo, hi) = Self::pair_bounds(&sorn1, &sorn2, op.name());
+
+				if min.is_none_or(|(best, _)| lo < best)
+				{
+					min = Some((lo, (i, j)));
+				}
+
+				if max.is_none_or(|(best, _)| hi > best)
+				{
+					max = Some((hi, (i, j)));
+				}
+			}
+		}
+
+		return Ok(OpResult { result: self.clone(), min_from: min.map(|(_, ij)| ij), max_from: max.map(|(_, ij)| ij) });
+	}
+
+	pub fn checked_add(&mut self, addend: &Self) -> Option<SornErrors>
+	{
+		Self::checked_op(self, addend, "add")
+	}
+
+	/*
+	Adds like checked_add and also propagates a weight per active bucket, given in the
+	order of ranges_ref. Each pair of buckets passes the product of its weights on in
+	equal parts to the buckets its sum reaches, the returned weights sum to 1 again.
+	*/
+	pub fn checked_add_weighted(&self, other: &Self, self_w: &[Real], other_w: &[Real]) -> Result<(Sorn, Vec<Real>), SornErrors>
+	{
+		if self.sorn_set != other.sorn_set
+		{
+			return Err(SornErrors::DifferentSornSets);
+		}
+
+		if self_w.len() != self.cardinality() || other_w.len() != other.cardinality()
+		{
+			return Err(SornErrors::DimensionMismatch);
+		}
+
+		let mut weights: Vec<Real> = vec![0.0; self.with_set(SornSet::len)];
+		let mut result = Sorn::new(self.sorn_set.clone());
+
+		for (i, a) in self.active_indices().into_iter().enumerate()
+		{
+			for (j, b) in other.active_indices().into_iter().enumerate()
+			{
+				let mut sum = Sorn::new(self.sorn_set.clone());
+				sum.bits = 1 << a;
+				let mut operand = Sorn::new(self.sorn_set.clone());
+				operand.bits = 1 << b;
+
+				if let Some(err) = sum.checked_add(&operand)
+				{
+					return Err(err);
+				}
+
+				let share = self_w[i] * other_w[j] / sum.cardinality().max(1) as Real;
+				for k in sum.active_indices()
+				{
+					weights[k] += share;
+				}
+
+				result.bits |= sum.bits;
+			}
+		}
+
+		let mut weights: Vec<Real> = result.active_indices().into_iter().map(|k| weights[k]).collect();
+		let total: Real = weights.iter().sum();
+
+		if total > 0.0
+		{
+			weights.iter_mut().for_each(|w| *w /= total);
+		}
+
+		return Ok((result, weights));
+	}
+
+	/* checked_add that also reports how cardinality and width of self changed */
+	pub fn checked_add_report(&mut self, operand: &Self) -> Result<OpReport, SornErrors>
+	{
+		let cardinality_before = self.cardinality();
+		let width_before = self.hull_width();
+
+		if let Some(err) = self.checked_add(operand)
+		{
+			return Err(err);
+		}
+
+		return Ok(OpReport
+		{
+			bits: self.bits,
+			cardinality_before,
+			cardinality_after: self.cardinality(),
+			width_before,
+			width_after: self.hull_width(),
+		});
+	}
+
+	/*
+	Like add, but the gaps between the lowest and highest active bucket are filled, so
+	the result is the single connected interval around the sum. [±inf] is kept as is.
+	Expects the set to be sorted, see SornSet::normalize.
+	*/
+	pub fn minkowski_sum(&self, other: &Self) -> Result<Sorn, SornErrors>
+	{
+		let mut sum = Sorn::add(self, other)?;
+
+		let inf = Sorn::sorn_to_bits(self.sorn_set.clone(), &SornValue::PlusMinusInf);
+		let finite = sum.bits & !inf;
+
+		if finite != 0
+		{
+			let low = finite.trailing_zeros();
+			let high = sorn_max_bits() as u32 - finite.leading_zeros();
+
+			sum.bits |= mask_for_len(high as usize) & !mask_for_len(low as usize);
+		}
+
+		return Ok(sum);
+	}
+
+	pub fn checked_sub(&mut self, addend: &Self) -> Option<SornErrors>
+	{
+		Self::checked_op(self, addend, "sub")
+	}
+
+	pub fn checked_mul(&mut self, addend: &Self) -> Option<SornErrors>
+	{
+		Self::checked_op(self, addend, "mul")
+	}
+
+	pub fn checked_div(&mut self, addend: &Self) -> Option<SornErrors>
+	{
+		Self::checked_op(self, addend, "div")
+	}
+
+	/*
+	self + other as a new Sorn, self is left unchanged. checked_add is the in place
+	version for accumulators, the operators are built on these.
+	*/
+	pub fn add(&self, other: &Self) -> Result<Sorn, SornErrors>
+	{
+		let mut sorn = self.clone();
+
+		return match sorn.checked_add(other)
+		{
+			Some(err) => Err(err),
+			None => Ok(sorn),
+		};
+	}
+
+	pub fn sub(&self, other: &Self) -> Result<Sorn, SornErrors>
+	{
+		let mut sorn = self.clone();
+
+		return match sorn.checked_sub(other)
+		{
+			Some(err) => Err(err),
+			None => Ok(sorn),
+		};
+	}
+
+	pub fn mul(&self, other: &Self) -> Result<Sorn, SornErrors>
+	{
+		let mut sorn = self.clone();
+
+		return match sorn.checked_mul(other)
+		{
+			Some(err) => Err(err),
+			None => Ok(sorn),
+		};
+	}
+
+	pub fn div(&self, other: &Self) -> Result<Sorn, SornErrors>
+	{
+		let mut sorn = self.clone();
+
+		return match sorn.checked_div(other)
+		{
+			Some(err) => Err(err),
+			None => Ok(sorn),
+		};
+	}
+
+	pub fn rem(&self, other: &Self) -> Result<Sorn, SornErrors>
+	{
+		let mut sorn = self.clone();
+
+		return match sorn.checked_rem(other)
+		{
+			Some(err) => Err(err),
+			None => Ok(sorn),
+		};
+	}
+
+	/*
+	Truncated remainder like Rust's %, so the result has the sign of the dividend.
+	[±inf] and divisors that contain 0 have no remainder and contribute nothing.
+	*/
+	fn rem_bits(set: Rc<RefCell<SornSet>>, sorn1: &SornValue, sorn2: &SornValue) -> SornBitsType
+	{
+		if sorn1.is_pminf() || sorn2.overlaps(&SornValue::Exact(0.0))
+		{
+			return 0;
+		}
+
+		let Some((a, a_closed, b, b_closed)) = sorn1.closed_bounds() else { return 0; };
+		let Some((c, _, e, _)) = sorn2.closed_bounds() else { return 0; };
+
+		/* The divisor is on one side of 0, only its magnitude matters. 0 can only be an open endpoint here */
+		let (min_div, max_div) = if e > 0.0 { (c, e) } else { (-e, -c) };
+
+		/* Without a change of quotient the range is only shifted */
+		if sorn2.is_exact() && (a / c).trunc() == (b / c).trunc()
+		{
+			return Self::interval_to_bits(set, a % c, b % c, !a_closed, !b_closed);
+		}
+
+		if Real::max(a.abs(), b.abs()) < min_div
+		{
+			return Self::interval_to_bits(set, a, b, !a_closed, !b_closed);
+		}
+
+		/* The range wraps, so every remainder up to the divisor or the dividend is possible */
+		let (lo, lo_open) = if a < 0.0 { (-Real::min(-a, max_div), -a >= max_div || !a_closed) } else { (0.0, false) };
+		let (hi, hi_open) = if b > 0.0 { (Real::min(b, max_div), b >= max_div || !b_closed) } else { (0.0, false) };
+
+		return Self::interval_to_bits(set, lo, hi, lo_open, hi_open);
+	}
+
+	pub fn checked_rem(&mut self, divisor: &Self) -> Option<SornErrors>
+	{
+		if self.sorn_set != divisor.sorn_set
+		{
+			return Some(SornErrors::DifferentSornSets);
+		}
+
+		let mut result: SornBitsType = 0;
+
+		for sorn1 in self.ranges_ref().iter()
+		{
+			for sorn2 in divisor.ranges_ref().iter()
+			{
+				result |= Self::rem_bits(self.sorn_set.clone(), sorn1, sorn2);
+			}
+		}
+
+		let _ = self.set_bits(result);
+
+		return None;
+	}
+
+	/*
+	Division that fails with DivByZero if the divisor may be 0 or get arbitrarily close to it,
+	and the set can't represent the unbounded result
+	*/
+	pub fn strict_div(&mut self, divisor: &Self) -> Result<Sorn, SornErrors>
+	{
+		let may_be_zero = divisor.ranges_ref().iter().any(|range| range.closed_bounds().is_some_and(|(min, _, max, _)| min <= 0.0 && 0.0 <= max));
+
+		if may_be_zero && !self.with_set(SornSet::contains_inf)
+		{
+			return Err(SornErrors::DivByZero);
+		}
+
+		if let Some(err) = self.checked_div(divisor)
+		{
+			return Err(err);
+		}
+
+		return Ok(self.clone());
+	}
+
+	/*
+	self * (1 / other). The reciprocal is projected onto the set before the product, so this
+	is usually looser than checked_div, which also handles divisors with 0 as an endpoint by
+	dividing by each side of 0 they reach. Useful to cross-check checked_div.
+	*/
+	pub fn div_via_reciprocal(&self, other: &Self) -> Result<Sorn, SornErrors>
+	{
+		if self.sorn_set != other.sorn_set
+		{
+			return Err(SornErrors::DifferentSornSets);
+		}
+
+		let reciprocal = other.clone().reciprocal();
+		let mut result = self.clone();
+
+		if let Some(err) = result.checked_mul(&reciprocal)
+		{
+			return Err(err);
+		}
+
+		return Ok(result);
+	}
+
+	/* Sum of the elementwise products, accumulated from the first element to the last */
+	pub fn dot(a: &[Sorn], b: &[Sorn]) -> Result<Sorn, SornErrors>
+	{
+		if a.len() != b.len() || a.is_empty()
+		{
+			return Err(SornErrors::DimensionMismatch);
+		}
+
+		let mut sum = a[0].clone();
+		if let Some(err) = sum.checked_mul(&b[0])
+		{
+			return Err(err);
+		}
+
+		for (x, y) in a.iter().zip(b.iter()).skip(1)
+		{
+			let mut product = x.clone();
+			if let Some(err) = product.checked_mul(y)
+			{
+				return Err(err);
+			}
+
+			if let Some(err) = sum.checked_add(&product)
+			{
+				return Err(err);
+			}
+		}
+
+		return Ok(sum);
+	}
+
+	pub fn matmul<const M: usize, const K: usize, const N: usize>(a: &[[Sorn; K]; M], b: &[[Sorn; N]; K]) -> Result<[[Sorn; N]; M], SornErrors>
+	{
+		let mut result: [[Sorn; N]; M] = core::array::from_fn(|_| core::array::from_fn(|_| Sorn::default()));
+
+		for (i, row) in a.iter().enumerate()
+		{
+			for j in 0..N
+			{
+				let column: Vec<Sorn> = b.iter().map(|b_row| b_row[j].clone()).collect();
+				result[i][j] = Sorn::dot(row, &column)?;
+			}
+		}
+
+		return Ok(result);
+	}
+}
+
+impl std::ops::Neg for Sorn
+{
+	type Output = Sorn;
+
+	fn neg(mut self) -> Self::Output 
+	{
+		self.negate()
+	}
+}
+
+/* Addition Operator */
+impl std::ops::Add for Sorn
+{
+	type Output = Sorn;
+
+	fn add(self, rhs: Self) -> Self::Output 
+	{
+		return Sorn::add(&self, &rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Add for &Sorn
+{
+	type Output = Sorn;
+
+	fn add(self, rhs: Self) -> Self::Output 
+	{
+		return Sorn::add(self, rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Add<&Sorn> for Sorn
+{
+	type Output = Sorn;
+
+	fn add(self, rhs: &Sorn) -> Self::Output 
+	{
+		return Sorn::add(&self, rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Add<Sorn> for &Sorn
+{
+	type Output = Sorn;
+
+	fn add(self, rhs: Sorn) -> Self::Output 
+	{
+		return Sorn::add(self, &rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::AddAssign<&Sorn> for Sorn
+{
+	fn add_assign(&mut self, rhs: &Sorn) 
+	{
+		let res = self.checked_add(rhs);
+
+		if res.is_some()
+		{
+			self.bits = 0;
+		}
+	}
+}
+
+/* Subtraction Operator */
+impl std::ops::Sub for Sorn
+{
+	type Output = Sorn;
+
+	fn sub(self, rhs: Self) -> Self::Output 
+	{
+		return Sorn::sub(&self, &rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Sub for &Sorn
+{
+	type Output = Sorn;
+
+	fn sub(self, rhs: Self) -> Self::Output 
+	{
+		return Sorn::sub(self, rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Sub<&Sorn> for Sorn
+{
+	type Output = Sorn;
+
+	fn sub(self, rhs: &Sorn) -> Self::Output 
+	{
+		return Sorn::sub(&self, rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Sub<Sorn> for &Sorn
+{
+	type Output = Sorn;
+
+	fn sub(self, rhs: Sorn) -> Self::Output 
+	{
+		return Sorn::sub(self, &rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::SubAssign<&Sorn> for Sorn
+{
+	fn sub_assign(&mut self, rhs: &Sorn) 
+	{
+		let res = self.checked_sub(rhs);
+
+		if res.is_some()
+		{
+			self.bits = 0;
+		}
+	}
+}
+
+/* Multiplication Operator */
+impl std::ops::Mul for Sorn
+{
+	type Output = Sorn;
+
+	fn mul(self, rhs: Self) -> Self::Output 
+	{
+		return Sorn::mul(&self, &rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Mul for &Sorn
+{
+	type Output = Sorn;
+
+	fn mul(self, rhs: Self) -> Self::Output 
+	{
+		return Sorn::mul(self, rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Mul<&Sorn> for Sorn
+{
+	type Output = Sorn;
+
+	fn mul(self, rhs: &Sorn) -> Self::Output 
+	{
+		return Sorn::mul(&self, rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Mul<Sorn> for &Sorn
+{
+	type Output = Sorn;
+
+	fn mul(self, rhs: Sorn) -> Self::Output 
+	{
+		return Sorn::mul(self, &rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::MulAssign<&Sorn> for Sorn
+{
+	fn mul_assign(&mut self, rhs: &Sorn) 
+	{
+		let res = self.checked_mul(rhs);
+
+		if res.is_some()
+		{
+			self.bits = 0;
+		}
+	}
+}
+
+/* Division Operator */
+impl std::ops::Div for Sorn
+{
+	type Output = Sorn;
+
+	fn div(self, rhs: Self) -> Self::Output 
+	{
+		return Sorn::div(&self, &rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Div for &Sorn
+{
+	type Output = Sorn;
+
+	fn div(self, rhs: Self) -> Self::Output 
+	{
+		return Sorn::div(self, rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Div<&Sorn> for Sorn
+{
+	type Output = Sorn;
+
+	fn div(self, rhs: &Sorn) -> Self::Output 
+	{
+		return Sorn::div(&self, rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Div<Sorn> for &Sorn
+{
+	type Output = Sorn;
+
+	fn div(self, rhs: Sorn) -> Self::Output 
+	{
+		return Sorn::div(self, &rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::DivAssign<&Sorn> for Sorn
+{
+	fn div_assign(&mut self, rhs: &Sorn) 
+	{
+		let res = self.checked_div(rhs);
+
+		if res.is_some()
+		{
+			self.bits = 0;
+		}
+	}
+}
+
+impl std::ops::Rem for Sorn
+{
+	type Output = Sorn;
+
+	fn rem(self, rhs: Self) -> Self::Output 
+	{
+		return Sorn::rem(&self, &rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Rem for &Sorn
+{
+	type Output = Sorn;
+
+	fn rem(self, rhs: Self) -> Self::Output 
+	{
+		return Sorn::rem(self, rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Rem<&Sorn> for Sorn
+{
+	type Output = Sorn;
+
+	fn rem(self, rhs: &Sorn) -> Self::Output 
+	{
+		return Sorn::rem(&self, rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::Rem<Sorn> for &Sorn
+{
+	type Output = Sorn;
+
+	fn rem(self, rhs: Sorn) -> Self::Output 
+	{
+		return Sorn::rem(self, &rhs).unwrap_or_else(|_| Sorn::new(self.sorn_set.clone()));
+	}
+}
+
+impl std::ops::RemAssign<&Sorn> for Sorn
+{
+	fn rem_assign(&mut self, rhs: &Sorn) 
+	{
+		let res = self.checked_rem(rhs);
+
+		if res.is_some()
+		{
+			self.bits = 0;
+		}
+	}
+}
+
+impl std::clone::Clone for Sorn
+{
+	fn clone(&self) -> Self 
+	{
+		Self
+		{
+			bits: self.bits,
+			sorn_set: self.sorn_set.clone(),
+
+			#[cfg(feature = "trace")]
+			provenance: self.provenance,
+		}
+	}
+}
+
+impl std::fmt::Display for Sorn
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
+	{
+		let range = self.get_ranges();
+
+		write!(f, "Bits: {:b} | Range: {:?}", self.bits, range)
+	}
+}
+
+impl Sorn
+{
+	pub fn to_string_hex(&self) -> String
+	{
+		format!("{:X}", self.bits)
+	}
+
+	pub fn to_string_hex_full(&self) -> String
+	{
+		let range = self.ranges_ref();
+
+		format!("{:X} | Range: {:?}", self.bits, range)
+	}
+
+	pub fn to_string_nobits(&self) -> String
+	{
+		let range = self.ranges_ref();
+
+		format!("{:?}", range)
+	}
+
+	fn bucket_width(range: &SornValue) -> Real
+	{
+		if range.is_pminf() { Real::INFINITY } else { range.max() - range.min() }
+	}
+
+	/* One representative point per active bucket, the value of exacts and the center of intervals, unbounded buckets are skipped */
+	pub fn bucket_midpoints(&self) -> Vec<Real>
+	{
+		return self.ranges_ref().iter()
+			.filter_map(SornValue::closed_bounds)
+			.filter(|(min, _, max, _)| min.is_finite() && max.is_finite())
+			.map(|(min, _, max, _)| min + (max - min) / 2.0)
+			.collect();
+	}
+
+	/*
+	Every active bucket with a weight proportional to its width, the weights sum to 1.
+	Exact buckets have no width and get 0, unless every bucket is exact or one of them
+	is unbounded, then each bucket gets the same share.
+	*/
+	pub fn to_distribution(&self) -> Vec<(SornValue, Real)>
+	{
+		let ranges = self.ranges_ref();
+		let total: Real = ranges.iter().map(Self::bucket_width).sum();
+
+		if total == 0.0 || !total.is_finite()
 		{
-			self.sorn_set.borrow_mut().precomputed_sub.insert((self.bits, operand.bits), result);
+			let share = 1.0 / ranges.len() as Real;
+
+			return ranges.iter().map(|range| (*range, share)).collect();
 		}
 
-		if operation == "mul"
+		return ranges.iter().map(|range| (*range, Self::bucket_width(range) / total)).collect();
+	}
+
+	/*
+	Every active bucket with its share of the total width, e.g. "(0,1): 40% | [1]: 0% | (1,2): 60%".
+	Exact buckets have no width. If the total width is infinite the shares are printed as "?".
+	*/
+	pub fn to_string_weighted(&self) -> String
+	{
+		let ranges = self.ranges_ref();
+		let total: Real = ranges.iter().map(Self::bucket_width).sum();
+
+		let parts: Vec<String> = ranges.iter().map(|range|
 		{
-			self.sorn_set.borrow_mut().precomputed_mul.insert((self.bits, operand.bits), result);
-			self.sorn_set.borrow_mut().precomputed_mul.insert((operand.bits, self.bits), result);
+			if !total.is_finite()
+			{
+				format!("{}: ?", range)
+			}
+			else if total == 0.0
+			{
+				format!("{}: 0%", range)
+			}
+			else
+			{
+				format!("{}: {:.0}%", range, Self::bucket_width(range) / total * 100.0)
+			}
+		}).collect();
+
+		return parts.join(" | ");
+	}
+
+	pub fn to_string_compact(&self) -> String
+	{
+		format!("{} to {}", self.get_min_range().unwrap().min(), self.get_max_range().unwrap().max())
+	}
+
+	/*
+	Draws the finite span of the set as width columns, '#' for active and '.' for inactive buckets.
+	Sets with infinity get a marker on each side, '<' or '>' if the infinite tail is active, '.' if not.
+	*/
+	pub fn to_ascii_line(&self, width: usize) -> String
+	{
+		let sorn_set = self.sorn_set.borrow();
+
+		let is_tail = |item: &SornValue| item.is_pminf() || item.min().is_infinite() || item.max().is_infinite();
+
+		let mut lo = Real::INFINITY;
+		let mut hi = Real::NEG_INFINITY;
+		for item in sorn_set.sets.iter()
+		{
+			for bound in [item.min(), item.max()]
+			{
+				if bound.is_finite()
+				{
+					lo = lo.min(bound);
+					hi = hi.max(bound);
+				}
+			}
 		}
 
-		if operation == "div"
+		if width == 0 || lo > hi
+		{
+			return String::new();
+		}
+
+		let span = if hi > lo { hi - lo } else { 1.0 };
+		let column = |x: Real| (x - lo) / span * width as Real;
+		let mut bar = vec!['.'; width];
+		let mut left_tail = false;
+		let mut right_tail = false;
+
+		for (i, item) in sorn_set.sets.iter().enumerate()
+		{
+			if self.bits & (1 << i) == 0
+			{
+				continue;
+			}
+
+			if is_tail(item)
+			{
+				left_tail |= item.is_pminf() || item.min() == Real::NEG_INFINITY;
+				right_tail |= item.is_pminf() || item.max() == Real::INFINITY;
+				continue;
+			}
+
+			/* Every active bucket gets at least one column */
+			let start = (column(item.min()).floor() as usize).min(width - 1);
+			let end = (column(item.max()).ceil() as usize).clamp(start + 1, width);
+
+			for c in bar.iter_mut().take(end).skip(start)
+			{
+				*c = '#';
+			}
+		}
+
+		let bar: String = bar.into_iter().collect();
+
+		if sorn_set.contains_inf
+		{
+			let left = if left_tail { '<' } else { '.' };
+			let right = if right_tail { '>' } else { '.' };
+
+			return format!("{} {} [{}] {} {}", left, lo, bar, hi, right);
+		}
+
+		return format!("{} [{}] {}", lo, bar, hi);
+	}
+}
+
+impl std::cmp::PartialEq for Sorn
+{
+	fn eq(&self, other: &Self) -> bool 
+	{
+		(self.sorn_set == other.sorn_set) && (self.bits == other.bits)
+	}
+}
+
+/* Testing */
+#[cfg(test)]
+mod tests 
+{
+    use crate::sorntable_gen;
+    use crate::sornset::{GapKind, GridStyle};
+
+    use super::*;
+	use super::SornValue::*;
+
+	#[test]
+	fn test_new()
+	{
+		
+	}
+
+	#[test]
+	fn test_sorn_to_bits_positive()
+	{
+		/* 
+		1: [0] 
+		10: (0, 1)
+		100: [1]
+		*/
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false)));
+		let sorn = Sorn::new(set.clone());
+		
+		/* Intervals */
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((0.0, 1.0)));
+		assert_eq!(value, 0b10);
+
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((0.0, 2.0)));
+		assert_eq!(value, 0b110);
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((0.0, 1.245345)));
+		assert_eq!(value, 0b110);
+
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((-1.0, 2.0)));
+		assert_eq!(value, 0b111);
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((-0.000000001, 2.0)));
+		assert_eq!(value, 0b111);
+
+		/* Exacts */
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(0.0));
+		assert_eq!(value, 0b001);
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(1.0));
+		assert_eq!(value, 0b100);
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(0.5));
+		assert_eq!(value, 0b010);
+	}
+
+	#[test]
+	fn test_sorn_to_bits_negative()
+	{
+		/* 
+		1: [-2] 
+		10: (-2, -1)
+		100: [-1]
+		*/
+		let set = Rc::new(RefCell::new(SornSet::new(-2.0, -1.0, 1.0, false)));
+		let sorn = Sorn::new(set.clone());
+	
+		/* Intervals */
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((-2.0, -1.0)));
+		assert_eq!(value, 0b10);
+
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((-2.0, 2.0)));
+		assert_eq!(value, 0b110);
+
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((-1.0, 2.0)));
+		assert_eq!(value, 0b000);
+
+		/* Exacts */
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(-2.0));
+		assert_eq!(value, 0b001);
+
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(-1.0));
+		assert_eq!(value, 0b100);
+
+		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(-1.5));
+		assert_eq!(value, 0b010);
+	}
+
+	#[test]
+	fn test_custom_new()
+	{
+		
+	}
+
+	#[test]
+	fn test_set_number()
+	{
+		
+	}
+
+	#[test]
+	fn test_set_bits()
+	{
+		
+	}
+
+	#[test]
+	fn test_posneg_noinf_add()
+	{
+		/* CSV Format */
+		let expected = 	",1,10,100,1000,10000,\n\
+						1,0,0,1,10,100,\n\
+						10,0,11,10,1110,1000,\n\
+						100,1,10,100,1000,10000,\n\
+						1000,10,1110,1000,11000,0,\n\
+						10000,100,1000,10000,0,0,\n\
+						";
+
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let sorn1 = Sorn::new(set.clone());
+		let table = sorntable_gen::gen_table(sorn1.sorn_set.clone(), "add");
+
+		println!("{}", table.to_csv());
+		println!("{}", expected);
+
+		assert_eq!(table.to_csv(), expected);
+	}
+
+	#[test]
+	fn test_neg_inf_add()
+	{
+		let expected = ",1,10,100,1000,10000,100000,\n\
+						1,1,1,1,1,1,1,\n\
+						10,1,10,10,10,10,111110,\n\
+						100,1,10,10,10,100,111000,\n\
+						1000,1,10,10,1110,1000,111000,\n\
+						10000,1,10,100,1000,10000,100000,\n\
+						100000,1,111110,111000,111000,100000,100000,\n\
+						";
+
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 0.0, 1.0, true)));
+		let sorn1 = Sorn::new(set.clone());
+		let table = sorntable_gen::gen_table(sorn1.sorn_set.clone(), "add");
+
+		println!("{}", table.to_csv());
+		println!("{}", expected);
+
+		assert_eq!(table.to_csv(), expected);
+	}
+
+	#[test]
+	fn test_expected_value()
+	{
+		/* [0], (0,1), [1], (1,2), [2], (2,3), [3], (3,4), [4] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, false)));
+		let mut sorn = Sorn::new(set.clone());
+
+		/* Only (0,1) and (3,4) are active, the hole in between is ignored */
+		sorn.set_bits(0b010000010).unwrap();
+		assert_eq!(sorn.expected_value(), Some(2.0));
+
+		sorn.set_bits(0b110000010).unwrap();
+		assert_eq!(sorn.expected_value(), Some((0.5 + 3.5 + 4.0) / 3.0));
+
+		sorn.set_bits(0).unwrap();
+		assert_eq!(sorn.expected_value(), None);
+
+		let inf_set = Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, true)));
+		let mut unbounded = Sorn::new(inf_set.clone());
+		unbounded.set_bits(0b100000).unwrap();
+		assert_eq!(unbounded.expected_value(), None);
+	}
+
+	#[test]
+	fn test_error_display()
+	{
+		assert_eq!(SornErrors::NotInRange.to_string(), "value not representable in this SORN set");
+		assert_eq!(SornErrors::DifferentSornSets.to_string(), "operands belong to different SORN sets");
+		assert_eq!(SornErrors::SetTooLarge.to_string(), "SORN set has more elements than SornBitsType has bits");
+		assert_eq!(SornErrors::DimensionMismatch.to_string(), "operands have mismatching dimensions");
+		assert_eq!(SornErrors::InvalidRange.to_string(), "range needs end > start and a positive step");
+		assert_eq!(SornErrors::DivByZero.to_string(), "divisor may be zero and the set has no infinity");
+		assert_eq!(SornErrors::PrecisionLoss.to_string(), "integer is not exactly representable as a float");
+		assert_eq!(SornErrors::NotSingleBucket.to_string(), "Sorn has no or more than one active bucket");
+
+		let boxed: Box<dyn std::error::Error> = Box::new(SornErrors::NotInRange);
+		assert_eq!(format!("{}", boxed), "value not representable in this SORN set");
+	}
+
+	#[test]
+	fn test_resample_conservative()
+	{
+		/* [0], (0,0.5), [0.5], (0.5,1), [1], (1,1.5), [1.5], (1.5,2), [2] */
+		let fine = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 0.5, false)));
+		/* [0], (0,1), [1], (1,2), [2] */
+		let coarse = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
+
+		/* (0.5,1), [1] and (1,1.5) straddle the coarse boundary at 1 */
+		let mut sorn = Sorn::new(fine.clone());
+		sorn.set_bits(0b000111000).unwrap();
+		assert_eq!(sorn.resample_conservative(coarse.clone()).bits, 0b01110);
+
+		/* A closed endpoint only touching the neighbour bucket must still set it */
+		let source = Rc::new(RefCell::new(SornSet::from_string("[1,2)")));
+		let target = Rc::new(RefCell::new(SornSet::from_string("(0,1];(1,3)")));
+		let mut sorn = Sorn::new(source.clone());
+		sorn.set_bits(0b1).unwrap();
+		assert_eq!(sorn.resample_conservative(target.clone()).bits, 0b11);
+	}
+
+	#[cfg(feature = "metrics")]
+	#[test]
+	fn test_cache_metrics()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let mut a = Sorn::new(set.clone());
+		let mut b = Sorn::new(set.clone());
+		a.set_bits(0b00010).unwrap();
+		b.set_bits(0b01000).unwrap();
+
+		for _ in 0..3
+		{
+			let _ = &a + &b;
+		}
+
+		let metrics = set.borrow().metrics();
+		assert_eq!(metrics.add.misses, 1);
+		assert_eq!(metrics.add.hits, 2);
+		assert_eq!(metrics.mul.hits + metrics.mul.misses, 0);
+	}
+
+	#[test]
+	fn test_checked_pow()
+	{
+		/* [0], (0,1), [1] is closed under squaring */
+		let closed = Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false)));
+		let mut sorn = Sorn::new(closed.clone());
+		sorn.set_bits(0b111).unwrap();
+		assert_eq!(sorn.checked_pow(2).unwrap().bits, 0b111);
+
+		/* [0], (0,1), [1], (1,2), [2] is not, [2] squared is [4] */
+		let open = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
+		let mut sorn = Sorn::new(open.clone());
+		sorn.set_bits(0b00110).unwrap();
+		assert_eq!(sorn.checked_pow(2).unwrap().bits, 0b00110);
+
+		sorn.set_bits(0b10000).unwrap();
+		assert!(matches!(sorn.checked_pow(2), Err(SornErrors::NotInRange)));
+		assert_eq!(sorn.pow(2).bits, 0);
+	}
+
+	#[test]
+	fn test_sign()
+	{
+		/* [-1], (-1,0), [0], (0,1), [1] */
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let mut sorn = Sorn::new(set.clone());
+
+		sorn.set_bits(0b11000).unwrap();
+		assert_eq!(sorn.sign(), Some(1));
+
+		sorn.set_bits(0b00011).unwrap();
+		assert_eq!(sorn.sign(), Some(-1));
+
+		sorn.set_bits(0b00100).unwrap();
+		assert_eq!(sorn.sign(), Some(0));
+
+		sorn.set_bits(0b01110).unwrap();
+		assert_eq!(sorn.sign(), None);
+
+		sorn.set_bits(0b01010).unwrap();
+		assert_eq!(sorn.sign(), None);
+
+		sorn.set_bits(0).unwrap();
+		assert_eq!(sorn.sign(), None);
+
+		/* Open ends touching zero still count as signed */
+		let touching = Rc::new(RefCell::new(SornSet::from_string("(0,1];[-1,0)")));
+		let mut sorn = Sorn::new(touching.clone());
+		sorn.set_bits(0b01).unwrap();
+		assert_eq!(sorn.sign(), Some(1));
+		sorn.set_bits(0b10).unwrap();
+		assert_eq!(sorn.sign(), Some(-1));
+	}
+
+	#[test]
+	fn test_from_interval()
+	{
+		/* [0], (0,1), [1], (1,2), [2] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
+
+		assert_eq!(Sorn::from_interval(set.clone(), 0.0, 1.0, true, true).bits, 0b00010);
+		assert_eq!(Sorn::from_interval(set.clone(), 0.0, 1.0, true, false).bits, 0b00110);
+		assert_eq!(Sorn::from_interval(set.clone(), 0.0, 1.0, false, true).bits, 0b00011);
+		assert_eq!(Sorn::from_interval(set.clone(), 0.0, 1.0, false, false).bits, 0b00111);
+		assert_eq!(Sorn::from_interval(set.clone(), 0.5, 1.5, false, false).bits, 0b01110);
+
+		assert_eq!(Sorn::from_interval(set.clone(), 1.0, 1.0, false, false).bits, 0b00100);
+		assert_eq!(Sorn::from_interval(set.clone(), 1.0, 1.0, true, false).bits, 0);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_from_interval_inverted()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
+		let _ = Sorn::from_interval(set.clone(), 2.0, 1.0, false, false);
+	}
+
+	#[test]
+	fn test_matmul()
+	{
+		/* [0], (0,1), [1], (1,2), [2], (2,3), [3], (3,4), [4] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, false)));
+		let value = |value: SornValue| Sorn::from_sornvalue(set.clone(), value);
+
+		let a = [[value(Exact(1.0)), value(Open((1.0, 2.0)))], [value(Exact(0.0)), value(Exact(1.0))]];
+		let b = [[value(Exact(1.0)), value(Exact(0.0))], [value(Exact(1.0)), value(Exact(1.0))]];
+
+		let result = Sorn::matmul(&a, &b).unwrap();
+
+		for i in 0..2
 		{
-			self.sorn_set.borrow_mut().precomputed_div.insert((self.bits, operand.bits), result);
-			self.sorn_set.borrow_mut().precomputed_div.insert((operand.bits, self.bits), result);
+			for j in 0..2
+			{
+				let expected = &(&a[i][0] * &b[0][j]) + &(&a[i][1] * &b[1][j]);
+				assert_eq!(result[i][j], expected);
+			}
 		}
-		
-		let res = self.set_bits(result);
-		//println!("{}", self.to_string());
 
-		return None;
+		/* 1 + (1,2) is (2,3), 0 * 0 + 1 * 1 is [1] */
+		assert_eq!(result[0][0].get_ranges().sets, vec![Open((2.0, 3.0))]);
+		assert_eq!(result[1][1].get_ranges().sets, vec![Exact(1.0)]);
 	}
 
-	pub fn checked_add(&mut self, addend: &Self) -> Option<SornErrors>
+	#[test]
+	fn test_dot_mismatch()
 	{
-		Self::checked_op(self, addend, "add")
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, false)));
+		let other = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
+
+		let a = [Sorn::new(set.clone()), Sorn::new(set.clone())];
+		assert!(matches!(Sorn::dot(&a, &a[..1]), Err(SornErrors::DimensionMismatch)));
+		assert!(matches!(Sorn::dot(&a[..1], &[Sorn::new(other.clone())]), Err(SornErrors::DifferentSornSets)));
 	}
 
-	pub fn checked_sub(&mut self, addend: &Self) -> Option<SornErrors>
+	#[test]
+	fn test_add_sub_closedness()
 	{
-		Self::checked_op(self, addend, "sub")
+		/* [0,1) + [2,3) = [2,4) */
+		assert_eq!(Sorn::add_sub_bounds(&OpenRight((0.0, 1.0)), &OpenRight((2.0, 3.0)), "add"), Some((2.0, true, 4.0, false)));
+		/* [0,1) - [2,3) = (-3,-1) */
+		assert_eq!(Sorn::add_sub_bounds(&OpenRight((0.0, 1.0)), &OpenRight((2.0, 3.0)), "sub"), Some((-3.0, false, -1.0, false)));
+		/* [5] - (2,3] = [2,3) */
+		assert_eq!(Sorn::add_sub_bounds(&Exact(5.0), &OpenLeft((2.0, 3.0)), "sub"), Some((2.0, true, 3.0, false)));
+		/* (0,1] + [1] = (1,2] */
+		assert_eq!(Sorn::add_sub_bounds(&OpenLeft((0.0, 1.0)), &Exact(1.0), "add"), Some((1.0, false, 2.0, true)));
+		assert_eq!(Sorn::add_sub_bounds(&PlusMinusInf, &Exact(1.0), "add"), None);
+
+		let set = Rc::new(RefCell::new(SornSet::from_string("[-3];(-3,-1);[-1];[0,1);[2,3);[4]")));
+		let a = Sorn::from_sornvalue(set.clone(), OpenRight((0.0, 1.0)));
+		let b = Sorn::from_sornvalue(set.clone(), OpenRight((2.0, 3.0)));
+
+		assert_eq!((&a + &b).get_ranges().sets, vec![OpenRight((2.0, 3.0))]);
+		/* Neither -3 nor -1 can be reached */
+		assert_eq!((&a - &b).get_ranges().sets, vec![Open((-3.0, -1.0))]);
 	}
 
-	pub fn checked_mul(&mut self, addend: &Self) -> Option<SornErrors>
+	#[test]
+	fn test_to_ascii_line()
 	{
-		Self::checked_op(self, addend, "mul")
+		/* [0], (0,1), [1], (1,2), [2], (2,3), [3], (3,4), [4] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, false)));
+		let mut sorn = Sorn::new(set.clone());
+
+		/* (0,1), [1] and (1,2) cover half of the span */
+		sorn.set_bits(0b000001110).unwrap();
+		let line = sorn.to_ascii_line(8);
+		assert_eq!(line, "0 [####....] 4");
+		assert_eq!(line.matches('#').count(), 4);
+
+		/* A single exact still shows up */
+		sorn.set_bits(0b100000000).unwrap();
+		assert_eq!(sorn.to_ascii_line(8), "0 [.......#] 4");
+
+		let inf_set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, true)));
+		let mut sorn = Sorn::new(inf_set.clone());
+		/* [±inf], (-inf,-1), [-1], (-1,0), [0], (0,1), [1], (1,inf) */
+		sorn.set_bits(0b10000010).unwrap();
+		assert_eq!(sorn.to_ascii_line(4), "< -1 [....] 1 >");
+
+		sorn.set_bits(0b00001000).unwrap();
+		assert_eq!(sorn.to_ascii_line(4), ". -1 [##..] 1 .");
 	}
 
-	pub fn checked_div(&mut self, addend: &Self) -> Option<SornErrors>
+	#[cfg(feature = "f32")]
+	#[test]
+	fn test_f32_backing()
 	{
-		Self::checked_op(self, addend, "div")
+		assert_eq!(std::mem::size_of::<Real>(), 4);
+
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 0.5, false)));
+		let a = Sorn::from_sornvalue(set.clone(), Exact(0.5));
+		let b = Sorn::from_sornvalue(set.clone(), Open((0.0, 0.5)));
+
+		assert_eq!((&a + &b).get_ranges().sets, vec![Open((0.5, 1.0))]);
+		assert_eq!((&a * &a).get_ranges().sets, vec![Open((0.0, 0.5))]);
 	}
-}
 
-impl std::ops::Neg for Sorn
-{
-	type Output = Sorn;
+	#[test]
+	fn test_enumerate_buckets()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let mut sorn = Sorn::new(set.clone());
+		sorn.set_bits(0b10110).unwrap();
 
-	fn neg(mut self) -> Self::Output 
+		assert_eq!(sorn.active_indices(), vec![1, 2, 4]);
+
+		let buckets: Vec<(usize, SornValue, bool)> = sorn.enumerate_buckets().collect();
+		assert_eq!(buckets.len(), 5);
+		assert_eq!(buckets[1], (1, Open((-1.0, 0.0)), true));
+		assert_eq!(buckets[3], (3, Open((0.0, 1.0)), false));
+
+		let active: Vec<usize> = buckets.iter().filter(|(_, _, active)| *active).map(|(i, _, _)| *i).collect();
+		assert_eq!(active, sorn.active_indices());
+	}
+
+	#[cfg(feature = "trace")]
+	#[test]
+	fn test_last_op()
 	{
-		self.negate()
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let a = Sorn::from_sornvalue(set.clone(), Open((-1.0, 0.0)));
+		let b = Sorn::from_sornvalue(set.clone(), Exact(1.0));
+
+		assert_eq!(a.last_op(), None);
+
+		let sum = &a + &b;
+		assert_eq!(sum.last_op(), Some((Op::Add, a.bits, b.bits)));
+
+		/* Cached results are traced as well */
+		let product = &a * &b;
+		let product = &product * &b;
+		assert_eq!(product.last_op(), Some((Op::Mul, 0b00010, b.bits)));
 	}
-}
 
-/* Addition Operator */
-impl std::ops::Add for Sorn
-{
-	type Output = Sorn;
+	#[test]
+	fn test_overlaps_across_sets()
+	{
+		/* [0], (0,0.5), [0.5], (0.5,1), [1], (1,1.5), [1.5], (1.5,2), [2] */
+		let fine = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 0.5, false)));
+		/* [0], (0,1), [1], (1,2), [2] */
+		let coarse = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
+
+		let fine_sorn = Sorn::from_sornvalue(fine.clone(), Open((0.5, 1.0)));
+		let coarse_sorn = Sorn::from_sornvalue(coarse.clone(), Open((0.0, 1.0)));
+		let coarse_far = Sorn::from_sornvalue(coarse.clone(), Open((1.0, 2.0)));
+
+		assert!(fine_sorn.overlaps_across_sets(&coarse_sorn));
+		assert!(coarse_sorn.overlaps_across_sets(&fine_sorn));
+
+		assert!(!fine_sorn.overlaps_across_sets(&coarse_far));
+		assert!(!coarse_far.overlaps_across_sets(&fine_sorn));
+
+		/* Disjoint on the fine set, but both lie in the coarse (0,1) */
+		let fine_low = Sorn::from_sornvalue(fine.clone(), Open((0.0, 0.5)));
+		assert!(!fine_low.overlaps_across_sets(&fine_sorn));
+		assert!(coarse_sorn.overlaps_across_sets(&fine_low));
+		assert!(coarse_sorn.overlaps_across_sets(&fine_sorn));
+	}
 
-	fn add(self, rhs: Self) -> Self::Output 
+	#[test]
+	fn test_with_set()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let a = Sorn::from_sornvalue(set.clone(), SornValue::Exact(1.0));
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_add(&rhs);
+		assert_eq!(a.with_set(SornSet::len), 5);
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		/* The borrow is released once the closure returns */
+		a.with_set(|s| s.len());
+		set.borrow_mut().clear_caches();
+	}
 
-		return sorn;
+	#[test]
+	fn test_pow_saturates_to_inf_bucket()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-2.0, 2.0, 1.0, true)));
+		let tail = Sorn::from_sornvalue(set.clone(), SornValue::Open((2.0, Real::INFINITY)));
+
+		let mut a = Sorn::from_sornvalue(set.clone(), SornValue::Exact(2.0));
+		assert_eq!(a.pow(5000).bits, tail.bits);
+
+		let mut b = Sorn::from_sornvalue(set.clone(), SornValue::OpenRight((1.0, 2.0)));
+		assert!(b.checked_pow(5000).unwrap().contains(SornValue::Open((2.0, Real::INFINITY))));
+
+		/* 0^-1 is the pole, like 1/0 and powf(-1.0) */
+		let mut zero = Sorn::from_sornvalue(set.clone(), SornValue::Exact(0.0));
+		let pinf = Sorn::from_sornvalue(set.clone(), SornValue::PlusMinusInf);
+		assert_eq!(zero.pow(-1).bits, pinf.bits);
+		assert_eq!(zero.pow(-1).bits, zero.powf(-1.0).bits);
+		assert_eq!(zero.pow(-2).bits, pinf.bits);
 	}
-}
 
-impl std::ops::Add for &Sorn
-{
-	type Output = Sorn;
+	#[test]
+	fn test_checked_negate()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 3.0, 1.0, false)));
 
-	fn add(self, rhs: Self) -> Self::Output 
+		let mut a = Sorn::from_sornvalue(set.clone(), SornValue::Exact(1.0));
+		assert!(a.checked_negate().unwrap().contains(SornValue::Exact(-1.0)));
+
+		let mut b = Sorn::from_sornvalue(set.clone(), SornValue::Exact(3.0));
+		assert!(matches!(b.checked_negate(), Err(SornErrors::NotInRange)));
+		assert_eq!(b.negate().bits, 0);
+	}
+
+	#[test]
+	fn test_checked_abs_and_sqrt()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 4.0, 1.0, false)));
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_add(rhs);
+		let mut a = Sorn::from_sornvalue(set.clone(), SornValue::Exact(-1.0));
+		assert!(a.checked_abs().unwrap().contains(SornValue::Exact(1.0)));
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		let mut b = Sorn::from_sornvalue(set.clone(), SornValue::Exact(4.0));
+		assert!(b.checked_sqrt().unwrap().contains(SornValue::Exact(2.0)));
 
-		return sorn;
+		/* [-1, 0) only contains negative values */
+		let mut c = Sorn::from_sornvalue(set.clone(), SornValue::OpenRight((-1.0, 0.0)));
+		assert!(matches!(c.checked_sqrt(), Err(SornErrors::NotInRange)));
+
+		/* sqrt drops the negative bucket, of (-1, 0] only the root of 0 is left */
+		let mut d = Sorn::from_sornvalue(set.clone(), SornValue::Open((-1.0, 0.0)));
+		let zero = Sorn::from_sornvalue(set.clone(), SornValue::Exact(0.0));
+		d.set_bits(d.bits | zero.bits).unwrap();
+		assert_eq!(d.sqrt().get_ranges().sets, vec![SornValue::Exact(0.0)]);
+		assert!(matches!(d.checked_sqrt(), Err(SornErrors::NotInRange)));
 	}
-}
 
-impl std::ops::Add<&Sorn> for Sorn
-{
-	type Output = Sorn;
+	#[test]
+	fn test_hamming_distance()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let a = Sorn::from_sornvalue(set.clone(), SornValue::Open((-1.0, 0.0)));
+		let mut b = a.clone();
 
-	fn add(self, rhs: &Sorn) -> Self::Output 
+		assert_eq!(a.hamming_distance(&b), Some(0));
+
+		b.set_bits(a.bits | 1).unwrap();
+		assert_eq!(a.hamming_distance(&b), Some(1));
+
+		let c = Sorn::from_sornvalue(set.clone(), SornValue::Open((0.0, 1.0)));
+		assert_eq!(b.hamming_distance(&c), Some(3));
+
+		let other = Sorn::from_sornvalue(Rc::new(RefCell::new(SornSet::new(-2.0, 2.0, 1.0, false))), SornValue::Exact(0.0));
+		assert_eq!(a.hamming_distance(&other), None);
+	}
+
+	#[test]
+	fn test_iterate()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 0.5, false)));
+		let half = Sorn::from_sornvalue(set.clone(), SornValue::Exact(0.5));
+		let one = Sorn::from_sornvalue(set.clone(), SornValue::Exact(1.0));
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_add(rhs);
+		/* 1 -> 0.5 -> (0, 0.5) -> (0, 0.5) */
+		let (fixpoint, iters) = one.iterate(|x| x * &half, 10);
+		assert_eq!(fixpoint.get_ranges().sets, vec![SornValue::Open((0.0, 0.5))]);
+		assert_eq!(iters, 3);
 
-		if res1.is_err() || res2.is_some()
+		/* Negation flips between 1 and -1 forever */
+		let (_, iters) = one.iterate(|x| x.clone().negate(), 10);
+		assert_eq!(iters, 2);
+
+		let (last, iters) = Sorn::from_sornvalue(set.clone(), SornValue::Exact(-1.0)).iterate(|x|
 		{
-			sorn.bits = 0;
-		}
+			let mut next = x.clone();
+			next.set_bits(x.bits << 1).unwrap();
+			next
+		}, 3);
+		assert_eq!(iters, 3);
+		assert_eq!(last.bits, 1 << 3);
+	}
 
-		return sorn;
+	#[test]
+	fn test_inf_propagation()
+	{
+		/* [±inf], (-inf,-1), [-1], (-1,0), [0], (0,1), [1], (1,inf) */
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, true)));
+		let inf = Sorn::from_sornvalue(set.clone(), SornValue::PlusMinusInf);
+		let zero = Sorn::from_sornvalue(set.clone(), SornValue::Exact(0.0));
+		let one = Sorn::from_sornvalue(set.clone(), SornValue::Exact(1.0));
+		let all: SornBitsType = 0b11111111;
+
+		assert_eq!((&one + &inf).bits, inf.bits);
+		assert_eq!((&inf + &one).bits, inf.bits);
+		/* Two [±inf] stay [±inf], as before the per-operation handling */
+		assert_eq!((&inf + &inf).bits, inf.bits);
+
+		assert_eq!((&one - &inf).bits, inf.bits);
+		assert_eq!((&inf - &inf).bits, inf.bits);
+
+		assert_eq!((&one * &inf).bits, inf.bits);
+		assert_eq!((&inf * &inf).bits, inf.bits);
+		assert_eq!((&zero * &inf).bits, all);
+
+		assert_eq!((&inf / &one).bits, inf.bits);
+		assert_eq!((&one / &inf).bits, zero.bits);
+		assert_eq!((&inf / &inf).bits, inf.bits);
+
+		/* The finite parts of an operand are still combined normally */
+		let mut inf_or_one = inf.clone();
+		inf_or_one.set_bits(inf.bits | one.bits).unwrap();
+		assert_eq!((&inf_or_one + &one).bits, inf.bits | (1 << 7));
 	}
-}
 
-impl std::ops::Add<Sorn> for &Sorn
-{
-	type Output = Sorn;
+	#[test]
+	fn test_sorn_to_bits_half_open_boundaries()
+	{
+		/* [0], (0,1], (1,2] */
+		let style = GridStyle { gaps: GapKind::OpenLeft, exact_points: false };
+		let set = Rc::new(RefCell::new(SornSet::new_with_style(0.0, 2.0, 1.0, false, style)));
+
+		/* The shared endpoint 1 belongs to (0,1] only */
+		assert_eq!(Sorn::sorn_to_bits(set.clone(), &Exact(1.0)), 0b010);
+		assert_eq!(Sorn::sorn_to_bits(set.clone(), &OpenRight((1.0, 1.5))), 0b110);
+		assert_eq!(Sorn::sorn_to_bits(set.clone(), &Open((1.0, 1.5))), 0b100);
+		assert_eq!(Sorn::sorn_to_bits(set.clone(), &OpenLeft((0.5, 1.0))), 0b010);
+		assert_eq!(Sorn::sorn_to_bits(set.clone(), &Exact(0.0)), 0b001);
+		assert_eq!(Sorn::sorn_to_bits(set.clone(), &Empty), 0);
+	}
 
-	fn add(self, rhs: Sorn) -> Self::Output 
+	#[test]
+	fn test_gap_count()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, false)));
+		let mut sorn = Sorn::new(set.clone());
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_add(&rhs);
+		assert_eq!(sorn.gap_count(), 0);
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		sorn.set_bits(0b000111000).unwrap();
+		assert_eq!(sorn.gap_count(), 0);
 
-		return sorn;
+		sorn.set_bits(0b110000011).unwrap();
+		assert_eq!(sorn.gap_count(), 1);
+
+		sorn.set_bits(0b101001001).unwrap();
+		assert_eq!(sorn.gap_count(), 3);
+
+		sorn.set_bits(0b100011001).unwrap();
+		assert_eq!(sorn.gap_count(), 2);
 	}
-}
 
-impl std::ops::AddAssign<&Sorn> for Sorn
-{
-	fn add_assign(&mut self, rhs: &Sorn) 
+	#[test]
+	fn test_from_f64_clamped()
 	{
-		let res = self.checked_add(&rhs);
-
-		if res.is_some()
-		{
-			self.bits = 0;
-		}
+		/* [0], (0,1), [1], (1,2), [2] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
+
+		assert_eq!(Sorn::from_f64(set.clone(), -5.0).bits, 0);
+		assert_eq!(Sorn::from_f64_clamped(set.clone(), -5.0).bits, 0b00001);
+		assert_eq!(Sorn::from_f64_clamped(set.clone(), 5.0).bits, 0b10000);
+		assert_eq!(Sorn::from_f64_clamped(set.clone(), 0.5).bits, 0b00010);
+
+		/* Far away values still pick the edge on their side */
+		assert_eq!(Sorn::from_f64_clamped(set.clone(), Real::MAX).bits, 0b10000);
+		assert_eq!(Sorn::from_f64_clamped(set.clone(), Real::INFINITY).bits, 0b10000);
+		assert_eq!(Sorn::from_f64_clamped(set.clone(), Real::MIN).bits, 0b00001);
+		assert_eq!(Sorn::from_f64_clamped(set.clone(), Real::NEG_INFINITY).bits, 0b00001);
+		assert_eq!(Sorn::from_f64_clamped(set.clone(), Real::NAN).bits, 0);
+
+		/* [0], (0,1], (1,2], 0 is only in the closing exact point */
+		let style = GridStyle { gaps: GapKind::OpenLeft, exact_points: false };
+		let half_open = Rc::new(RefCell::new(SornSet::new_with_style(0.0, 2.0, 1.0, false, style)));
+		assert_eq!(Sorn::from_f64_clamped(half_open.clone(), 0.0).bits, 0b001);
 	}
-}
 
-/* Subtraction Operator */
-impl std::ops::Sub for Sorn
-{
-	type Output = Sorn;
+	#[test]
+	fn test_checked_op_explained()
+	{
+		/* [-1], (-1,0), [0], (0,1), [1], (1,2), [2] */
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 2.0, 1.0, false)));
+		let mut a = Sorn::new(set.clone());
+		a.set_bits(0b1000001).unwrap();
+		let mut b = Sorn::new(set.clone());
+		b.set_bits(0b1010000).unwrap();
+
+		let explained = a.clone().checked_op_explained(&b, Op::Mul).unwrap();
+		assert_eq!(explained.result, &a * &b);
+
+		/* -1 * 2 is the lowest and 2 * 2 the highest product */
+		let product = |(i, j): (usize, usize)| set.borrow().get(i).get().unwrap() * set.borrow().get(j).get().unwrap();
+		assert_eq!(explained.min_from, Some((0, 6)));
+		assert_eq!(product(explained.min_from.unwrap()), -2.0);
+		assert_eq!(explained.max_from, Some((6, 6)));
+		assert_eq!(product(explained.max_from.unwrap()), 4.0);
+
+		let other = Sorn::new(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))));
+		assert!(matches!(a.checked_op_explained(&other, Op::Add), Err(SornErrors::DifferentSornSets)));
+	}
 
-	fn sub(self, rhs: Self) -> Self::Output 
+	#[test]
+	fn test_ranges_ref()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, true)));
+		let mut sorn = Sorn::new(set.clone());
+		sorn.set_bits(0b10010101).unwrap();
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_sub(&rhs);
+		let view = sorn.ranges_ref();
+		let ranges = sorn.get_ranges();
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		assert_eq!(view.len(), ranges.len());
+		assert!(view.iter().eq(ranges.sets.iter()));
+		assert_eq!(view.get(1), Some(&ranges.get(1)));
+		assert_eq!(view.get(4), None);
+		assert_eq!(format!("{:?}", view), format!("{:?}", ranges));
+		drop(view);
 
-		return sorn;
+		assert!(Sorn::new(set.clone()).ranges_ref().is_empty());
 	}
-}
 
-impl std::ops::Sub for &Sorn
-{
-	type Output = Sorn;
+	#[test]
+	fn test_checked_rem()
+	{
+		/* [0], (0,1), [1], (1,2), [2], (2,3), [3], (3,4), [4] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, false)));
+		let value = |v: SornValue| Sorn::from_sornvalue(set.clone(), v);
+
+		/* Smaller than the divisor stays as is */
+		assert_eq!(&value(Exact(1.0)) % &value(Exact(3.0)), value(Exact(1.0)));
+		assert_eq!(&value(Open((1.0, 2.0))) % &value(Exact(3.0)), value(Open((1.0, 2.0))));
+
+		/* Larger is wrapped */
+		assert_eq!(&value(Exact(4.0)) % &value(Exact(3.0)), value(Exact(1.0)));
+		assert_eq!(&value(Open((3.0, 4.0))) % &value(Exact(3.0)), value(Open((0.0, 1.0))));
+
+		/* Spanning a multiple of the divisor covers [0, divisor) */
+		let wrapped = &value(Open((2.0, 4.0))) % &value(Exact(3.0));
+		assert_eq!(wrapped.get_ranges().sets, vec![Exact(0.0), Open((0.0, 1.0)), Exact(1.0), Open((1.0, 2.0)), Exact(2.0), Open((2.0, 3.0))]);
+
+		/* Interval divisors, the magnitude is bounded by the open end 0 of (0,1) */
+		assert_eq!(&value(Open((0.0, 1.0))) % &value(Open((2.0, 3.0))), value(Open((0.0, 1.0))));
+		assert_eq!((&value(Open((3.0, 4.0))) % &value(Open((0.0, 1.0)))).get_ranges().sets, vec![Exact(0.0), Open((0.0, 1.0))]);
+		assert_eq!((&value(Exact(3.0)) % &value(Open((0.0, 1.0)))).get_ranges().sets, vec![Exact(0.0), Open((0.0, 1.0))]);
+		assert_eq!((&value(Open((3.0, 4.0))) % &value(Open((1.0, 2.0)))).get_ranges().sets, vec![Exact(0.0), Open((0.0, 1.0)), Exact(1.0), Open((1.0, 2.0))]);
+
+		/* A divisor containing 0 has no remainder */
+		assert_eq!((&value(Exact(2.0)) % &value(Exact(0.0))).bits, 0);
+
+		let mut a = value(Exact(1.0));
+		let other = Sorn::new(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))));
+		assert!(matches!(a.checked_rem(&other), Some(SornErrors::DifferentSornSets)));
+	}
 
-	fn sub(self, rhs: Self) -> Self::Output 
+	#[test]
+	fn test_wrap_angle()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let tau = std::f64::consts::TAU as Real;
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_sub(rhs);
+		/* [0], (0,1), ..., (6,7), [7] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 7.0, 1.0, false)));
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		let mut angle = Sorn::from_sornvalue(set.clone(), Open((6.0, 7.0)));
+		let wrapped = angle.wrap_angle(tau);
+		assert_eq!(wrapped.get_ranges().sets, vec![Exact(0.0), Open((0.0, 1.0)), Open((6.0, 7.0))]);
 
-		return sorn;
-	}
-}
+		let mut negative = Sorn::from_sornvalue(Rc::new(RefCell::new(SornSet::new(-1.0, 7.0, 1.0, false))), Exact(-1.0));
+		assert!(negative.wrap_angle(tau).contains(Open((5.0, 6.0))));
 
-impl std::ops::Sub<&Sorn> for Sorn
-{
-	type Output = Sorn;
+		/* A full rotation covers [0, 2pi) */
+		let mut full = Sorn::from_interval(set.clone(), 0.0, 7.0, false, false);
+		let wrapped = full.wrap_angle(tau);
+		assert_eq!(wrapped.get_ranges().sets.len(), 14);
+		assert!(!wrapped.contains(Exact(7.0)));
 
-	fn sub(self, rhs: &Sorn) -> Self::Output 
+		/* A single bucket wider than a period collapses onto [0, 2pi) */
+		let coarse = Rc::new(RefCell::new(SornSet::new(0.0, 8.0, 8.0, false)));
+		let mut wide = Sorn::from_sornvalue(coarse.clone(), Open((0.0, 8.0)));
+		assert_eq!(wide.wrap_angle(tau).get_ranges().sets, vec![Exact(0.0), Open((0.0, 8.0))]);
+	}
+
+	#[test]
+	fn test_sin_cos()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		/* [-2], (-2,-1.5), [-1.5], ..., (1.5,2), [2] */
+		let set = Rc::new(RefCell::new(SornSet::new(-2.0, 2.0, 0.5, false)));
+		let value = |v: SornValue| Sorn::from_sornvalue(set.clone(), v);
+
+		/* (1.5,2) contains pi/2, so the maximum 1 is reached */
+		assert_eq!(value(Open((1.5, 2.0))).sin().get_ranges().sets, vec![Open((0.5, 1.0)), Exact(1.0)]);
+
+		/* (0,0.5) is in the rising region and maps monotonically onto (0,sin 0.5) */
+		assert_eq!(value(Open((0.0, 0.5))).sin().get_ranges().sets, vec![Open((0.0, 0.5))]);
+		assert_eq!(value(Open((-2.0, -1.5))).sin().get_ranges().sets, vec![Exact(-1.0), Open((-1.0, -0.5))]);
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_sub(rhs);
+		assert_eq!(value(Exact(0.0)).cos().get_ranges().sets, vec![Exact(1.0)]);
 
-		if res1.is_err() || res2.is_some()
+		/* The open end at 0 never reaches the maximum of cos */
+		assert_eq!(value(Open((-0.5, 0.0))).cos().get_ranges().sets, vec![Open((0.5, 1.0))]);
+
+		let coarse = Rc::new(RefCell::new(SornSet::new(-8.0, 8.0, 16.0, false)));
+		let mut wide = Sorn::from_sornvalue(coarse.clone(), Open((-8.0, 8.0)));
+		assert_eq!(wide.sin().get_ranges().sets, vec![Open((-8.0, 8.0))]);
+	}
+
+	#[test]
+	fn test_valid_mask()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false)));
+		assert_eq!(Sorn::new(set.clone()).valid_mask(), 0b111);
+		assert_eq!(Sorn::default().valid_mask(), 0);
+
+		/* A set that fills every bit must not overflow the shift */
+		let mut full = SornSet::default();
+		for i in 0..sorn_max_bits()
 		{
-			sorn.bits = 0;
+			full.push(Exact(i as Real));
 		}
+		let mut sorn = Sorn::new(Rc::new(RefCell::new(full)));
+		assert_eq!(sorn.valid_mask(), SornBitsType::MAX);
+		assert!(sorn.set_bits(SornBitsType::MAX).is_ok());
+	}
 
-		return sorn;
+	#[test]
+	fn test_full()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, true)));
+		let full = Sorn::full(set.clone());
+		let x = Sorn::from_interval(set.clone(), -0.5, 1.0, false, true);
+
+		assert_eq!(full.cardinality(), set.borrow().len());
+		assert_eq!(full.intersect(&x).unwrap(), x);
+		assert_eq!(x.intersect(&full).unwrap(), x);
+		assert_eq!(Sorn::new(set.clone()).cardinality(), 0);
+
+		let other = Sorn::full(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))));
+		assert!(matches!(full.intersect(&other), Err(SornErrors::DifferentSornSets)));
 	}
-}
 
-impl std::ops::Sub<Sorn> for &Sorn
-{
-	type Output = Sorn;
+	#[test]
+	fn test_shift()
+	{
+		/* [0], (0,1), [1], (1,2), [2] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
+		let one = Sorn::from_sornvalue(set.clone(), Exact(1.0));
+
+		assert_eq!(one.shift_up(ShiftMode::Drop).get_ranges().sets, vec![Open((1.0, 2.0))]);
+		assert_eq!(one.shift_down(ShiftMode::Drop).get_ranges().sets, vec![Open((0.0, 1.0))]);
+
+		let two = Sorn::from_sornvalue(set.clone(), Exact(2.0));
+		assert_eq!(two.shift_up(ShiftMode::Drop).bits, 0);
+		assert_eq!(two.shift_up(ShiftMode::Saturate), two);
+
+		let zero = Sorn::from_sornvalue(set.clone(), Exact(0.0));
+		assert_eq!(zero.shift_down(ShiftMode::Drop).bits, 0);
+		assert_eq!(zero.shift_down(ShiftMode::Saturate), zero);
+
+		/* Wrapping carries the edge bucket to the other end */
+		let mut edges = Sorn::from_sornvalue(set.clone(), Exact(2.0));
+		edges.set_bits(edges.bits | 0b10).unwrap();
+		assert_eq!(edges.shift_up(ShiftMode::Wrap).bits, 0b00101);
+		assert_eq!(edges.shift_up(ShiftMode::Saturate).bits, 0b10100);
+		assert_eq!(edges.shift_up(ShiftMode::Drop).bits, 0b00100);
+		assert_eq!(zero.shift_down(ShiftMode::Wrap), two);
+	}
 
-	fn sub(self, rhs: Sorn) -> Self::Output 
+	#[test]
+	fn test_strict_div()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let finite = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let mut one = Sorn::from_sornvalue(finite.clone(), Exact(1.0));
+		let zero = Sorn::from_sornvalue(finite.clone(), Exact(0.0));
+		let around_zero = Sorn::from_interval(finite.clone(), -0.5, 0.5, true, true);
+
+		assert!(matches!(one.strict_div(&zero), Err(SornErrors::DivByZero)));
+		assert!(matches!(one.strict_div(&around_zero), Err(SornErrors::DivByZero)));
+		assert_eq!(one.strict_div(&one.clone()).unwrap(), one);
+
+		/* With infinity the unbounded quotient is representable */
+		let inf = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, true)));
+		let mut one = Sorn::from_sornvalue(inf.clone(), Exact(1.0));
+		let positive = Sorn::from_sornvalue(inf.clone(), OpenRight((0.0, 1.0)));
+		let quotient = one.strict_div(&positive).unwrap();
+		assert!(quotient.contains(Open((1.0, Real::INFINITY))));
+
+		/* 0 as an open endpoint still makes the quotient unbounded */
+		let below_zero = Sorn::from_sornvalue(finite.clone(), Open((-1.0, 0.0)));
+		let mut one = Sorn::from_sornvalue(finite.clone(), Exact(1.0));
+		assert!(matches!(one.strict_div(&below_zero), Err(SornErrors::DivByZero)));
+
+		/* [±inf], (-inf,-2), [-2], (-2,-1), [-1], (-1,0), [0], (0,1), [1], (1,2), [2], (2,inf) */
+		let inf = Rc::new(RefCell::new(SornSet::new(-2.0, 2.0, 1.0, true)));
+		let value = |v: SornValue| Sorn::from_sornvalue(inf.clone(), v);
+		let quotient = value(Exact(1.0)).strict_div(&value(Open((-1.0, 0.0)))).unwrap();
+		assert_eq!(quotient.get_ranges().sets, vec![Open((Real::NEG_INFINITY, -2.0)), Exact(-2.0), Open((-2.0, -1.0))]);
+		assert!(quotient.soundly_contains(1.0 / -0.75));
+
+		/* A [0] dividend stays 0 on either side of 0 */
+		assert_eq!(value(Exact(0.0)).strict_div(&value(Open((0.0, 1.0)))).unwrap().get_ranges().sets, vec![Exact(0.0)]);
+		assert_eq!(value(Exact(0.0)).strict_div(&value(Open((-1.0, 0.0)))).unwrap().get_ranges().sets, vec![Exact(0.0)]);
+		assert_eq!((&value(Exact(0.0)) / &value(Open((1.0, 2.0)))).get_ranges().sets, vec![Exact(0.0)]);
+	}
+
+	#[test]
+	fn test_to_string_weighted()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 0.5, true)));
+		let sorn = Sorn::from_interval(set.clone(), 0.0, 1.5, true, true);
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_sub(&rhs);
+		let weighted = sorn.to_string_weighted();
+		assert_eq!(weighted, "(0,0.5): 33% | [0.5]: 0% | (0.5,1): 33% | [1]: 0% | (1,1.5): 33%");
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		let sum: Real = weighted.split(" | ")
+			.map(|part| part.rsplit(": ").next().unwrap().trim_end_matches('%').parse::<Real>().unwrap())
+			.sum();
+		assert!((sum - 100.0).abs() <= 1.0);
 
-		return sorn;
+		let unbounded = Sorn::from_sornvalue(set.clone(), Open((2.0, Real::INFINITY)));
+		assert_eq!(unbounded.to_string_weighted(), "(2,inf): ?");
 	}
-}
 
-impl std::ops::SubAssign<&Sorn> for Sorn
-{
-	fn sub_assign(&mut self, rhs: &Sorn) 
+	#[test]
+	fn test_pow_cache_per_exponent()
 	{
-		let res = self.checked_sub(&rhs);
+		let set = Rc::new(RefCell::new(SornSet::new(-2.0, 8.0, 1.0, false)));
+		let mut two = Sorn::from_sornvalue(set.clone(), Exact(2.0));
 
-		if res.is_some()
-		{
-			self.bits = 0;
-		}
+		assert!(two.pow(2).contains(Exact(4.0)));
+		assert!(two.pow(3).contains(Exact(8.0)));
+		assert!(two.pow(2).contains(Exact(4.0)));
 	}
-}
 
-/* Multiplication Operator */
-impl std::ops::Mul for Sorn
-{
-	type Output = Sorn;
+	#[test]
+	fn test_set_bits_masked()
+	{
+		/* [0], (0,1), [1] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false)));
+		let mut strict = Sorn::new(set.clone());
+		let mut masked = Sorn::new(set.clone());
 
-	fn mul(self, rhs: Self) -> Self::Output 
+		assert!(matches!(strict.set_bits(0b11010), Err(SornErrors::NotInRange)));
+		assert_eq!(strict.bits, 0);
+
+		masked.set_bits_masked(0b11010);
+		assert_eq!(masked.bits, 0b010);
+
+		strict.set_bits(0b101).unwrap();
+		masked.set_bits_masked(0b101);
+		assert_eq!(strict, masked);
+	}
+
+	#[test]
+	fn test_is_disjoint()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
+		let low = Sorn::from_interval(set.clone(), 0.0, 1.0, false, false);
+		let high = Sorn::from_interval(set.clone(), 1.0, 2.0, false, false);
+		let top = Sorn::from_sornvalue(set.clone(), Exact(2.0));
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_mul(&rhs);
+		assert_eq!(low.is_disjoint(&high), Some(false));
+		assert_eq!(low.is_disjoint(&top), Some(true));
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		let other = Sorn::new(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))));
+		assert_eq!(low.is_disjoint(&other), None);
+	}
 
-		return sorn;
+	#[test]
+	fn test_to_distribution()
+	{
+		/* [0], (0,1), [1], (1,3), [3] */
+		let mut set = SornSet::new(0.0, 1.0, 1.0, false);
+		set.push(Open((1.0, 3.0)));
+		set.push(Exact(3.0));
+		let set = Rc::new(RefCell::new(set));
+
+		let sorn = Sorn::from_interval(set.clone(), 0.5, 2.0, true, true);
+		let distribution = sorn.to_distribution();
+
+		let sum: Real = distribution.iter().map(|(_, weight)| weight).sum();
+		assert!((sum - 1.0).abs() < 1e-6);
+		assert_eq!(distribution, vec![(Open((0.0, 1.0)), 1.0 / 3.0), (Exact(1.0), 0.0), (Open((1.0, 3.0)), 2.0 / 3.0)]);
+
+		let exacts = Sorn::from_interval(set.clone(), 0.0, 0.0, false, false);
+		assert_eq!(exacts.to_distribution(), vec![(Exact(0.0), 1.0)]);
+
+		assert!(Sorn::new(set.clone()).to_distribution().is_empty());
 	}
-}
 
-impl std::ops::Mul for &Sorn
-{
-	type Output = Sorn;
+	#[test]
+	fn test_checked_op_exact()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 0.5, false)));
+		let mut a = Sorn::from_sornvalue(set.clone(), Open((0.0, 0.5)));
+		let b = a.clone();
 
-	fn mul(self, rhs: Self) -> Self::Output 
+		let (lo, hi) = a.checked_op_exact(&b, Op::Mul).unwrap();
+		assert_eq!((lo, hi), (0.0, 0.25));
+
+		/* The projection can only widen the exact result */
+		a.checked_mul(&b);
+		let projected = a.get_max_range().unwrap().max() - a.get_min_range().unwrap().min();
+		assert_eq!(projected, 0.5);
+		assert!(projected >= hi - lo);
+
+		assert!(matches!(Sorn::new(set.clone()).checked_op_exact(&b, Op::Add), Err(SornErrors::NotInRange)));
+
+		/* [-1], (-1,0), [0], (0,1), [1], 1 / [-1,1] is unbounded */
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let one = Sorn::from_f64(set.clone(), 1.0);
+		let mut around_zero = Sorn::new(set.clone());
+		around_zero.set_bits(0b11111).unwrap();
+		assert_eq!(one.checked_op_exact(&around_zero, Op::Div).unwrap(), (Real::NEG_INFINITY, Real::INFINITY));
+
+		let below_zero = Sorn::from_sornvalue(set.clone(), Open((-1.0, 0.0)));
+		assert_eq!(one.checked_op_exact(&below_zero, Op::Div).unwrap(), (Real::NEG_INFINITY, Real::INFINITY));
+
+		/* Away from 0 the quotient bounds come from the endpoints */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, false)));
+		let four = Sorn::from_f64(set.clone(), 4.0);
+		let one_to_two = Sorn::from_interval(set.clone(), 1.0, 2.0, false, false);
+		assert_eq!(four.checked_op_exact(&one_to_two, Op::Div).unwrap(), (2.0, 4.0));
+	}
+
+	#[test]
+	fn test_powf()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		/* [±inf], (-inf,-2), [-2], ..., [4], (4,inf) */
+		let set = Rc::new(RefCell::new(SornSet::new(-2.0, 4.0, 0.5, true)));
+		let value = |v: SornValue| Sorn::from_sornvalue(set.clone(), v);
+		let one = value(Exact(1.0));
+
+		/* Away from 0 the reciprocal equals 1 / x */
+		for v in [Exact(2.0), Open((0.5, 1.0)), OpenRight((-2.0, -1.5)), Open((1.0, Real::INFINITY))]
+		{
+			assert_eq!(value(v).powf(-1.0), &one / &value(v));
+			assert_eq!(value(v).reciprocal(), value(v).powf(-1.0));
+		}
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_mul(rhs);
+		/* 1 / 0 is the pole, (0, 0.5) is reciprocated onto (2, inf) */
+		assert_eq!(value(Exact(0.0)).powf(-1.0), value(PlusMinusInf));
+		assert_eq!(value(Open((0.0, 0.5))).reciprocal().get_ranges().sets, vec![Open((2.0, 2.5)), Exact(2.5), Open((2.5, 3.0)), Exact(3.0), Open((3.0, 3.5)), Exact(3.5), Open((3.5, 4.0)), Exact(4.0), Open((4.0, Real::INFINITY))]);
 
-		if res1.is_err() || res2.is_some()
+		/* Square roots like sqrt, negative values have none */
+		for v in [Exact(4.0), Open((1.0, 1.5)), OpenLeft((-0.5, 0.0)), Open((-2.0, -1.5))]
 		{
-			sorn.bits = 0;
+			assert_eq!(value(v).powf(0.5), value(v).sqrt());
 		}
 
-		return sorn;
+		assert_eq!(value(Exact(-2.0)).powf(2.0), value(Exact(4.0)));
+		assert_eq!(value(Open((-2.0, 3.0))).powf(0.0), one);
 	}
-}
 
-impl std::ops::Mul<&Sorn> for Sorn
-{
-	type Output = Sorn;
+	#[test]
+	fn test_compare_trilean()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, true)));
+
+		let low = Sorn::from_f64(set.clone(), 0.5);
+		let one = Sorn::from_f64(set.clone(), 1.0);
+		let high = Sorn::from_f64(set.clone(), 3.5);
+
+		/* definitely less, (0,1) against [1] only touches in the open end */
+		assert_eq!(low.lt(&one), Trilean::True);
+		assert_eq!(low.lt(&high), Trilean::True);
+		assert_eq!(low.le(&high), Trilean::True);
+		assert_eq!(low.eq_interval(&high), Trilean::False);
+
+		/* definitely greater */
+		assert_eq!(high.gt(&low), Trilean::True);
+		assert_eq!(high.lt(&low), Trilean::False);
+		assert_eq!(high.ge(&one), Trilean::True);
+		assert_eq!(one.le(&one), Trilean::True);
+		assert_eq!(one.lt(&one), Trilean::False);
+		assert_eq!(one.eq_interval(&one), Trilean::True);
+
+		/* overlapping */
+		let mut wide = Sorn::new(set.clone());
+		wide.set_bits(low.bits | one.bits | high.bits).unwrap();
+		assert_eq!(wide.lt(&one), Trilean::Unknown);
+		assert_eq!(wide.ge(&one), Trilean::Unknown);
+		assert_eq!(wide.eq_interval(&one), Trilean::Unknown);
+		assert_eq!(low.lt(&low), Trilean::Unknown);
+
+		/* [±inf] could be anything */
+		let inf = Sorn::from_f64(set.clone(), Real::INFINITY);
+		assert_eq!(inf.lt(&low), Trilean::Unknown);
+	}
 
-	fn mul(self, rhs: &Sorn) -> Self::Output 
+	#[test]
+	fn test_split_at()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		/* [0], (0,1), [1], (1,2), [2] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
+		let mut sorn = Sorn::new(set.clone());
+		sorn.set_bits(0b11110).unwrap();
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_mul(rhs);
+		/* inside the active bucket (0,1) */
+		let (below, above) = sorn.split_at(0.5);
+		assert_eq!(below.bits, 0b00010);
+		assert_eq!(above.bits, 0b11110);
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		/* on the exact point [1], (0,1) is below and [1] is not */
+		let (below, above) = sorn.split_at(1.0);
+		assert_eq!(below.bits, 0b00010);
+		assert_eq!(above.bits, 0b11100);
 
-		return sorn;
+		let (below, above) = sorn.split_at(5.0);
+		assert_eq!(below.bits, sorn.bits);
+		assert_eq!(above.bits, 0);
 	}
-}
 
-impl std::ops::Mul<Sorn> for &Sorn
-{
-	type Output = Sorn;
-
-	fn mul(self, rhs: Sorn) -> Self::Output 
+	#[test]
+	fn test_collect_ranges_into()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, true)));
+		let mut sorn = Sorn::new(set.clone());
+		sorn.set_bits(0b1010011).unwrap();
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_mul(&rhs);
+		let mut buffer = vec![SornValue::Empty];
+		sorn.collect_ranges_into(&mut buffer);
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		assert_eq!(buffer[0], SornValue::Empty);
+		assert_eq!(buffer[1..], sorn.get_ranges().sets[..]);
 
-		return sorn;
+		buffer.clear();
+		Sorn::new(set.clone()).collect_ranges_into(&mut buffer);
+		assert!(buffer.is_empty());
 	}
-}
 
-impl std::ops::MulAssign<&Sorn> for Sorn
-{
-	fn mul_assign(&mut self, rhs: &Sorn) 
+	#[test]
+	fn test_enclosing_exacts()
 	{
-		let res = self.checked_mul(&rhs);
+		/* [0], (0,1), [1], (1,2), [2], (2,3), [3] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 3.0, 1.0, true)));
 
-		if res.is_some()
-		{
-			self.bits = 0;
-		}
-	}
-}
+		let mut sorn = Sorn::from_f64(set.clone(), 1.5);
+		assert_eq!(sorn.enclosing_exacts(), Some((1.0, 2.0)));
 
-/* Division Operator */
-impl std::ops::Div for Sorn
-{
-	type Output = Sorn;
+		sorn.set_bits(Sorn::from_f64(set.clone(), 0.5).bits | Sorn::from_f64(set.clone(), 2.0).bits).unwrap();
+		assert_eq!(sorn.enclosing_exacts(), Some((0.0, 2.0)));
 
-	fn div(self, rhs: Self) -> Self::Output 
+		assert_eq!(Sorn::from_f64(set.clone(), 5.0).enclosing_exacts(), None);
+		assert_eq!(Sorn::from_f64(set.clone(), Real::INFINITY).enclosing_exacts(), None);
+		assert_eq!(Sorn::new(set.clone()).enclosing_exacts(), None);
+
+		/* Only [0] is exact, so nothing encloses the span from above */
+		let style = GridStyle { gaps: GapKind::OpenLeft, exact_points: false };
+		let set = Rc::new(RefCell::new(SornSet::new_with_style(0.0, 3.0, 1.0, false, style)));
+		assert_eq!(Sorn::from_f64(set.clone(), 1.5).enclosing_exacts(), None);
+	}
+
+	#[test]
+	fn test_from_scalars()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 3.0, 1.0, false)));
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_div(&rhs);
+		let sorn = Sorn::from_scalars(set.clone(), &[0.5, 2.0, 0.25, 7.0, Real::NAN, 2.0]);
+		let expected = Sorn::from_f64(set.clone(), 0.5).bits | Sorn::from_f64(set.clone(), 2.0).bits;
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		assert_eq!(sorn.bits, expected);
+		assert_eq!(sorn.get_ranges().sets, vec![SornValue::Open((0.0, 1.0)), SornValue::Exact(2.0)]);
 
-		return sorn;
+		assert_eq!(Sorn::from_scalars(set.clone(), &[]).bits, 0);
 	}
-}
-
-impl std::ops::Div for &Sorn
-{
-	type Output = Sorn;
 
-	fn div(self, rhs: Self) -> Self::Output 
+	#[test]
+	fn test_hull_of()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 3.0, 1.0, false)));
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_div(rhs);
+		let values = [0.5, 2.0, 0.25, 2.5].map(|x| Sorn::from_f64(set.clone(), x));
+		let hull = Sorn::hull_of(values);
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		assert_eq!(hull.get_ranges().sets, vec![SornValue::Open((0.0, 1.0)), SornValue::Exact(2.0), SornValue::Open((2.0, 3.0))]);
 
-		return sorn;
+		let empty = Sorn::hull_of(Vec::new());
+		assert_eq!(empty.bits, 0);
+		assert!(empty.with_set(SornSet::is_empty));
 	}
-}
-
-impl std::ops::Div<&Sorn> for Sorn
-{
-	type Output = Sorn;
 
-	fn div(self, rhs: &Sorn) -> Self::Output 
+	#[test]
+	fn test_checked_add_weighted()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		/* [0], (0,1), [1], (1,2), [2], (2,3), [3], (3,4), [4] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, false)));
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_div(rhs);
+		let dice = Sorn::from_scalars(set.clone(), &[1.0, 2.0]);
+		let (sum, weights) = dice.checked_add_weighted(&dice, &[0.5, 0.5], &[0.5, 0.5]).unwrap();
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		assert_eq!(sum.get_ranges().sets, vec![SornValue::Exact(2.0), SornValue::Exact(3.0), SornValue::Exact(4.0)]);
+		assert_eq!(weights, vec![0.25, 0.5, 0.25]);
 
-		return sorn;
-	}
-}
+		/* (0,1) + (0,1) reaches (0,1), [1] and (1,2), the pair weight is spread evenly */
+		let open = Sorn::from_f64(set.clone(), 0.5);
+		let (sum, weights) = open.checked_add_weighted(&open, &[1.0], &[1.0]).unwrap();
+		assert_eq!(sum.get_ranges().sets, vec![SornValue::Open((0.0, 1.0)), SornValue::Exact(1.0), SornValue::Open((1.0, 2.0))]);
+		assert!(weights.iter().all(|w| (w - 1.0 / 3.0).abs() < 1e-6));
 
-impl std::ops::Div<Sorn> for &Sorn
-{
-	type Output = Sorn;
+		assert!(matches!(dice.checked_add_weighted(&dice, &[1.0], &[0.5, 0.5]), Err(SornErrors::DimensionMismatch)));
+	}
 
-	fn div(self, rhs: Sorn) -> Self::Output 
+	#[test]
+	fn test_pack_unpack()
 	{
-		let mut sorn = Sorn::new(self.sorn_set.clone());
+		let mut registry = SornSetRegistry::new();
+		let small = registry.intern(SornSet::new(0.0, 1.0, 1.0, false));
+		let set = registry.intern(SornSet::new(0.0, 4.0, 1.0, true));
 
-		let res1 = sorn.set_bits(self.bits);
-		let res2 = sorn.checked_div(&rhs);
+		let sorn = Sorn::from_scalars(set.clone(), &[0.5, 3.0]);
+		let id = registry.id_of(&set.borrow()).unwrap();
+		assert_eq!(id, 1);
 
-		if res1.is_err() || res2.is_some()
-		{
-			sorn.bits = 0;
-		}
+		let packed = sorn.pack(id);
+		assert_eq!(packed, (1, sorn.bits));
 
-		return sorn;
+		let unpacked = Sorn::unpack(&registry, packed).unwrap();
+		assert_eq!(unpacked, sorn);
+		assert!(Rc::ptr_eq(&unpacked.sorn_set, &set));
+
+		assert!(Sorn::unpack(&registry, (7, sorn.bits)).is_none());
+		assert!(Sorn::unpack(&registry, (0, sorn.bits)).is_none());
+		assert_eq!(registry.id_of(&small.borrow()), Some(0));
 	}
-}
 
-impl std::ops::DivAssign<&Sorn> for Sorn
-{
-	fn div_assign(&mut self, rhs: &Sorn) 
+	#[test]
+	fn test_is_contiguous()
 	{
-		let res = self.checked_div(&rhs);
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, false)));
+		let mut sorn = Sorn::new(set.clone());
+		assert!(!sorn.is_contiguous());
 
-		if res.is_some()
-		{
-			self.bits = 0;
-		}
+		sorn.set_bits(0b000111100).unwrap();
+		assert!(sorn.is_contiguous());
+
+		sorn.set_bits(0b110000011).unwrap();
+		assert!(!sorn.is_contiguous());
+
+		sorn.set_bits(0b000010000).unwrap();
+		assert!(sorn.is_contiguous());
 	}
-}
 
-impl std::clone::Clone for Sorn
-{
-	fn clone(&self) -> Self 
+	#[test]
+	fn test_from_integers()
 	{
-		Self { bits: self.bits.clone(), sorn_set: self.sorn_set.clone() }
+		let set = Rc::new(RefCell::new(SornSet::new(-2.0, 2.0, 1.0, false)));
+
+		assert_eq!(Sorn::from_i64(set.clone(), -1).unwrap().get_ranges().sets, vec![SornValue::Exact(-1.0)]);
+		assert_eq!(Sorn::from_u64(set.clone(), 2).unwrap().get_ranges().sets, vec![SornValue::Exact(2.0)]);
+
+		/* Out of range saturates at the edges */
+		assert_eq!(Sorn::from_i64(set.clone(), -1000).unwrap().get_ranges().sets, vec![SornValue::Exact(-2.0)]);
+		assert_eq!(Sorn::from_u64(set.clone(), 1 << 20).unwrap().get_ranges().sets, vec![SornValue::Exact(2.0)]);
+
+		/* Powers of two stay exact, their odd neighbours don't */
+		let limit: u64 = 1 << Real::MANTISSA_DIGITS;
+		assert_eq!(Sorn::from_u64(set.clone(), limit).unwrap().get_ranges().sets, vec![SornValue::Exact(2.0)]);
+		assert_eq!(Sorn::from_u64(set.clone(), limit - 1).unwrap().get_ranges().sets, vec![SornValue::Exact(2.0)]);
+		assert_eq!(Sorn::from_i64(set.clone(), -(limit as i64)).unwrap().get_ranges().sets, vec![SornValue::Exact(-2.0)]);
+
+		/* Far above the set, but still exactly representable */
+		assert_eq!(Sorn::from_u64(set.clone(), 1 << 60).unwrap().get_ranges().sets, vec![SornValue::Exact(2.0)]);
+		assert_eq!(Sorn::from_i64(set.clone(), i64::MIN).unwrap().get_ranges().sets, vec![SornValue::Exact(-2.0)]);
+		assert!(matches!(Sorn::from_u64(set.clone(), limit + 1), Err(SornErrors::PrecisionLoss)));
+		assert!(matches!(Sorn::from_i64(set.clone(), -(limit as i64) - 1), Err(SornErrors::PrecisionLoss)));
+		assert!(matches!(Sorn::from_i64(set.clone(), i64::MAX), Err(SornErrors::PrecisionLoss)));
+		assert!(matches!(Sorn::from_u64(set.clone(), u64::MAX), Err(SornErrors::PrecisionLoss)));
 	}
-}
 
-impl std::string::ToString for Sorn
-{
-	fn to_string(&self) -> String 
+	#[test]
+	fn test_checked_op_footprint()
 	{
-		let range = self.get_ranges();
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, true)));
+		let a = Sorn::from_scalars(set.clone(), &[0.5, 1.0, 3.5]);
+		let b = Sorn::from_scalars(set.clone(), &[2.0, 2.5]);
+
+		assert_eq!(a.checked_op_footprint(&b, Op::Mul).unwrap(), a.cardinality() * b.cardinality());
+		assert_eq!(a.checked_op_footprint(&b, Op::Add).unwrap(), 6);
+		assert_eq!(a.checked_op_footprint(&Sorn::new(set.clone()), Op::Add).unwrap(), 0);
 
-		format!("Bits: {:b} | Range: {:?}", self.bits, range)
+		let other = Sorn::from_f64(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))), 0.5);
+		assert!(matches!(a.checked_op_footprint(&other, Op::Add), Err(SornErrors::DifferentSornSets)));
 	}
-}
 
-impl Sorn
-{
-	pub fn to_string_hex(&self) -> String
+	#[test]
+	fn test_bucket_midpoints()
 	{
-		let range = self.get_ranges();
+		/* [±inf], (-inf,0), [0], (0,0.5), [0.5], (0.5,1), [1], (1,inf) */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 0.5, true)));
 
-		format!("{:X}", self.bits)
+		let sorn = Sorn::from_scalars(set.clone(), &[-5.0, 0.25, 0.5, 0.75, 9.0, Real::INFINITY]);
+		assert_eq!(sorn.cardinality(), 6);
+		assert_eq!(sorn.bucket_midpoints(), vec![0.25, 0.5, 0.75]);
+
+		assert!(Sorn::new(set.clone()).bucket_midpoints().is_empty());
 	}
 
-	pub fn to_string_hex_full(&self) -> String
+	#[test]
+	fn test_try_into_value()
 	{
-		let range = self.get_ranges();
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
 
-		format!("{:X} | Range: {:?}", self.bits, range)
+		assert_eq!(Sorn::from_f64(set.clone(), 0.5).try_into_value().unwrap(), SornValue::Open((0.0, 1.0)));
+		assert!(matches!(Sorn::from_scalars(set.clone(), &[0.5, 2.0]).try_into_value(), Err(SornErrors::NotSingleBucket)));
+		assert!(matches!(Sorn::new(set.clone()).try_into_value(), Err(SornErrors::NotSingleBucket)));
 	}
 
-	pub fn to_string_nobits(&self) -> String
+	#[test]
+	fn test_with_active()
 	{
-		let range = self.get_ranges();
+		/* [0], (0,1), [1], (1,2), [2] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
 
-		format!("{:?}", range)
+		let sorn = Sorn::with_active(set.clone(), &[SornValue::Exact(2.0), SornValue::Open((0.0, 1.0)), SornValue::Exact(0.0), SornValue::Open((0.0, 2.0)), SornValue::Exact(7.0)]);
+		assert_eq!(sorn.bits, 0b10011);
+
+		assert_eq!(Sorn::with_active(set.clone(), &[]).bits, 0);
 	}
 
-	pub fn to_string_compact(&self) -> String
+	#[test]
+	fn test_mask_round_trip()
 	{
-		let range = self.get_ranges();
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, true)));
+		let sorn = Sorn::from_scalars(set.clone(), &[-3.0, 1.0, 1.5]);
 
-		format!("{} to {}", range.get_min_range().unwrap().min(), range.get_max_range().unwrap().max())
+		let mask = sorn.to_mask();
+		assert_eq!(mask, vec![false, true, false, false, true, true, false, false]);
+		assert_eq!(Sorn::from_mask(set.clone(), &mask).unwrap(), sorn);
+
+		assert!(matches!(Sorn::from_mask(set.clone(), &[true]), Err(SornErrors::DimensionMismatch)));
 	}
-}
 
-impl std::cmp::PartialEq for Sorn
-{
-	fn eq(&self, other: &Self) -> bool 
+	#[test]
+	fn test_div_via_reciprocal()
 	{
-		(self.sorn_set == other.sorn_set) && (self.bits == other.bits)
+		/* [±inf], (-inf,-2), [-2], (-2,-1), [-1], (-1,0), [0], (0,1), [1], (1,2), [2], (2,inf) */
+		let set = Rc::new(RefCell::new(SornSet::new(-2.0, 2.0, 1.0, true)));
+		let value = |v: SornValue| Sorn::from_sornvalue(set.clone(), v);
+
+		/* Away from 0 the direct division is tighter, 1/2 is projected onto (0,1) before the product */
+		let two = value(Exact(2.0));
+		assert_eq!((&two / &two).get_ranges().sets, vec![Exact(1.0)]);
+		assert_eq!(two.div_via_reciprocal(&two).unwrap().get_ranges().sets, vec![Open((0.0, 1.0)), Exact(1.0), Open((1.0, 2.0))]);
+
+		/* (2,inf) / (-1,0) is (-inf,-2), both see that 0 is approached from below */
+		let large = value(Open((2.0, Real::INFINITY)));
+		let below_zero = value(Open((-1.0, 0.0)));
+		assert_eq!(large.div_via_reciprocal(&below_zero).unwrap().get_ranges().sets, vec![Open((Real::NEG_INFINITY, -2.0))]);
+		assert_eq!(&large / &below_zero, large.div_via_reciprocal(&below_zero).unwrap());
+
+		/* Both hold the true quotient of sampled values, also for divisors with 0 as an endpoint */
+		let samples = [-100.0, -1.5, -1.0, -0.25, 0.0, 0.001, 0.5, 1.0, 2.0, 3.0, 100.0];
+		for x in samples
+		{
+			for y in samples.iter().filter(|y| **y != 0.0)
+			{
+				let (a, b) = (Sorn::from_f64(set.clone(), x), Sorn::from_f64(set.clone(), *y));
+
+				assert!((&a / &b).soundly_contains(x / y), "{} / {}", x, y);
+				assert!(a.div_via_reciprocal(&b).unwrap().soundly_contains(x / y), "{} / {}", x, y);
+			}
+		}
+
+		let other = Sorn::from_f64(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))), 0.5);
+		assert!(matches!(two.div_via_reciprocal(&other), Err(SornErrors::DifferentSornSets)));
 	}
-}
 
-/* Testing */
-#[cfg(test)]
-mod tests 
-{
-    use crate::sorntable_gen;
+	#[test]
+	fn test_empty()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, true)));
+		let empty = Sorn::empty(set.clone());
+		let x = Sorn::from_scalars(set.clone(), &[0.5, 2.0]);
 
-    use super::*;
-	use super::SornValue::*;
+		assert_eq!(empty.union(&x).unwrap(), x);
+		assert_eq!(x.union(&empty).unwrap(), x);
+		assert_eq!(empty.intersect(&x).unwrap(), empty);
+		assert_eq!(x.intersect(&empty).unwrap(), empty);
+
+		assert_eq!(&empty + &x, empty);
+		assert_eq!(&x * &empty, empty);
+
+		let other = Sorn::empty(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))));
+		assert!(matches!(x.union(&other), Err(SornErrors::DifferentSornSets)));
+	}
 
 	#[test]
-	fn test_new()
+	fn test_inactive_ranges()
 	{
-		
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, true)));
+		let sorn = Sorn::from_scalars(set.clone(), &[-4.0, 0.5, 1.0]);
+
+		let active = sorn.get_ranges().sets;
+		let inactive = sorn.inactive_ranges().sets;
+
+		assert_eq!(inactive, vec![SornValue::PlusMinusInf, SornValue::Exact(0.0), SornValue::Open((1.0, 2.0)), SornValue::Exact(2.0), SornValue::Open((2.0, Real::INFINITY))]);
+		assert_eq!(active.len() + inactive.len(), set.borrow().len());
+		assert!(set.borrow().sets.iter().all(|item| active.contains(item) != inactive.contains(item)));
+
+		assert_eq!(Sorn::full(set.clone()).inactive_ranges().sets, vec![]);
+		assert_eq!(Sorn::empty(set.clone()).inactive_ranges().sets, set.borrow().sets);
 	}
 
 	#[test]
-	fn test_sorn_to_bits_positive()
+	fn test_approx_eq()
 	{
-		/* 
-		1: [0] 
-		10: (0, 1)
-		100: [1]
-		*/
-		let mut set = Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false)));
-		let mut sorn = Sorn::new(set.clone());
-		
-		/* Intervals */
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((0.0, 1.0)));
-		assert_eq!(value, 0b10);
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false)));
 
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((0.0, 2.0)));
-		assert_eq!(value, 0b110);
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((0.0, 1.245345)));
-		assert_eq!(value, 0b110);
+		/* (0,1) + (0,1) against a result that lost the edge bucket (1,2) */
+		let a = Sorn::from_f64(set.clone(), 0.5);
+		let sum = &a + &a;
+		let mut rounded = sum.clone();
+		rounded.set_bits(sum.bits & !Sorn::from_f64(set.clone(), 1.5).bits).unwrap();
 
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((-1.0, 2.0)));
-		assert_eq!(value, 0b111);
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((-0.000000001, 2.0)));
-		assert_eq!(value, 0b111);
+		assert_ne!(sum, rounded);
+		assert!(sum.approx_eq(&rounded, 1));
+		assert!(!sum.approx_eq(&rounded, 0));
+		assert!(!sum.approx_eq(&Sorn::empty(set.clone()), 2));
 
-		/* Exacts */
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(0.0));
-		assert_eq!(value, 0b001);
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(1.0));
-		assert_eq!(value, 0b100);
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(0.5));
-		assert_eq!(value, 0b010);
+		let other = Sorn::from_f64(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))), 0.5);
+		assert!(!a.approx_eq(&other, 10));
 	}
 
 	#[test]
-	fn test_sorn_to_bits_negative()
+	fn test_checked_add_report()
 	{
-		/* 
-		1: [-2] 
-		10: (-2, -1)
-		100: [-1]
-		*/
-		let set = Rc::new(RefCell::new(SornSet::new(-2.0, -1.0, 1.0, false)));
-		let mut sorn = Sorn::new(set.clone());
-	
-		/* Intervals */
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((-2.0, -1.0)));
-		assert_eq!(value, 0b10);
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 4.0, 1.0, true)));
+		let mut sorn = Sorn::from_f64(set.clone(), 0.5);
+		let operand = Sorn::from_f64(set.clone(), 1.5);
+
+		/* (0,1) + (1,2) is (1,3), three buckets over a width of 2 */
+		let report = sorn.checked_add_report(&operand).unwrap();
+		assert_eq!(report.bits, sorn.bits);
+		assert_eq!((report.cardinality_before, report.cardinality_after), (1, 3));
+		assert_eq!((report.width_before, report.width_after), (1.0, 2.0));
+		assert!(report.width_after >= report.width_before);
+
+		let mut big = Sorn::from_f64(set.clone(), 3.5);
+		let report = big.checked_add_report(&operand).unwrap();
+		assert_eq!(report.width_after, Real::INFINITY);
+
+		let other = Sorn::from_f64(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))), 0.5);
+		assert!(matches!(sorn.checked_add_report(&other), Err(SornErrors::DifferentSornSets)));
+	}
 
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((-2.0, 2.0)));
-		assert_eq!(value, 0b110);
+	#[test]
+	fn test_project_modes()
+	{
+		/* [0], (0,1), [1], (1,2), [2], (2,3), [3] */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 3.0, 1.0, false)));
+		let value = SornValue::Open((0.5, 2.0));
 
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Open((-1.0, 2.0)));
-		assert_eq!(value, 0b000);
+		let outward = Sorn::project(set.clone(), value, ProjectionMode::Outward);
+		let inward = Sorn::project(set.clone(), value, ProjectionMode::Inward);
+		let nearest = Sorn::project(set.clone(), value, ProjectionMode::Nearest);
 
-		/* Exacts */
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(-2.0));
-		assert_eq!(value, 0b001);
+		assert_eq!(outward.get_ranges().sets, vec![Open((0.0, 1.0)), Exact(1.0), Open((1.0, 2.0))]);
+		assert_eq!(inward.get_ranges().sets, vec![Exact(1.0), Open((1.0, 2.0))]);
+		assert_eq!(nearest.get_ranges().sets, vec![Open((1.0, 2.0))]);
 
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(-1.0));
-		assert_eq!(value, 0b100);
+		/* The closed end keeps [2] inward */
+		let closed = Sorn::project(set.clone(), SornValue::OpenLeft((0.5, 2.0)), ProjectionMode::Inward);
+		assert_eq!(closed.get_ranges().sets, vec![Exact(1.0), Open((1.0, 2.0)), Exact(2.0)]);
 
-		let value = Sorn::sorn_to_bits(sorn.sorn_set.clone(), &Exact(-1.5));
-		assert_eq!(value, 0b010);
+		/* Too narrow to cover any bucket */
+		assert_eq!(Sorn::project(set.clone(), SornValue::Open((0.2, 0.4)), ProjectionMode::Inward).bits, 0);
+		assert_eq!(Sorn::project(set.clone(), SornValue::Exact(1.0), ProjectionMode::Inward).get_ranges().sets, vec![Exact(1.0)]);
+
+		/* Far above the set Nearest saturates at the top */
+		let far = SornValue::Open((Real::MAX / 2.0, Real::MAX));
+		assert_eq!(Sorn::project(set.clone(), far, ProjectionMode::Nearest).get_ranges().sets, vec![Exact(3.0)]);
 	}
 
 	#[test]
-	fn test_custom_new()
+	fn test_one_zero()
 	{
-		
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, true)));
+		let one = Sorn::one(set.clone()).unwrap();
+		let zero = Sorn::zero(set.clone()).unwrap();
+
+		assert_eq!(one.get_ranges().sets, vec![Exact(1.0)]);
+		assert_eq!(zero.get_ranges().sets, vec![Exact(0.0)]);
+
+		for x in SornSet::all_sorns(set.clone()).unwrap()
+		{
+			assert_eq!(&x * &one, x);
+			assert_eq!(&x + &zero, x);
+		}
+
+		/* 1 and 0 are only inside open buckets here */
+		let style = GridStyle { gaps: GapKind::Open, exact_points: false };
+		let set = Rc::new(RefCell::new(SornSet::new_with_style(-0.5, 1.5, 1.0, false, style)));
+		assert!(Sorn::one(set.clone()).is_none());
+		assert!(Sorn::zero(set.clone()).is_none());
 	}
 
 	#[test]
-	fn test_set_number()
+	fn test_non_mutating_ops()
 	{
-		
+		let set = Rc::new(RefCell::new(SornSet::new(-2.0, 2.0, 1.0, true)));
+		let a = Sorn::from_f64(set.clone(), 0.5);
+		let b = Sorn::from_f64(set.clone(), 1.5);
+
+		assert_eq!(a.add(&b).unwrap(), &a + &b);
+		assert_eq!(a.sub(&b).unwrap(), &a - &b);
+		assert_eq!(a.mul(&b).unwrap(), &a * &b);
+		assert_eq!(a.div(&b).unwrap(), &a / &b);
+		assert_eq!(b.rem(&a).unwrap(), &b % &a);
+
+		/* Neither operand changed */
+		assert_eq!(a, Sorn::from_f64(set.clone(), 0.5));
+		assert_eq!(b, Sorn::from_f64(set.clone(), 1.5));
+
+		let other = Sorn::from_f64(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))), 0.5);
+		assert!(matches!(a.add(&other), Err(SornErrors::DifferentSornSets)));
+		assert_eq!(a, Sorn::from_f64(set.clone(), 0.5));
+		assert_eq!((&a + &other).bits, 0);
 	}
 
 	#[test]
-	fn test_set_bits()
+	fn test_sort_sorns()
 	{
-		
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 3.0, 1.0, false)));
+		let sorn = |values: &[Real]| Sorn::from_scalars(set.clone(), values);
+
+		let mut sorns = vec![sorn(&[2.5]), sorn(&[]), sorn(&[0.5, 2.0]), sorn(&[1.0]), sorn(&[0.5]), sorn(&[0.0])];
+		sort_sorns(&mut sorns);
+
+		assert_eq!(sorns, vec![sorn(&[0.0]), sorn(&[0.5]), sorn(&[0.5, 2.0]), sorn(&[1.0]), sorn(&[2.5]), sorn(&[])]);
 	}
 
 	#[test]
-	fn test_posneg_noinf_add()
+	fn test_soundly_contains()
 	{
-		/* CSV Format */
-		let expected = 	",1,10,100,1000,10000,\n\
-						1,0,0,1,10,100,\n\
-						10,0,11,10,1110,1000,\n\
-						100,1,10,100,1000,10000,\n\
-						1000,10,1110,1000,11000,0,\n\
-						10000,100,1000,10000,0,0,\n\
-						";
-
-		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
-		let sorn1 = Sorn::new(set.clone());
-		let table = sorntable_gen::gen_table(sorn1.sorn_set.clone(), "add");
+		let set = Rc::new(RefCell::new(SornSet::new(-2.0, 2.0, 0.5, true)));
 
-		println!("{}", table.to_csv());
-		println!("{}", expected);
+		for (x, y) in [(0.3, 0.4), (0.25, 0.25), (-1.2, 0.7), (1.5, 1.5), (0.0, -0.5)]
+		{
+			let sum = Sorn::from_f64(set.clone(), x) + Sorn::from_f64(set.clone(), y);
+			assert!(sum.soundly_contains(x + y), "{} + {}", x, y);
+		}
 
-		assert_eq!(table.to_csv(), expected);
+		/* [0.5] + [0.5] is [1], the open neighbours don't contain their endpoint 1 */
+		let half = Sorn::from_f64(set.clone(), 0.5);
+		let sum = &half + &half;
+		assert!(sum.soundly_contains(1.0));
+		assert!(!sum.soundly_contains(1.0 + 1e-3));
+		assert!(!Sorn::from_f64(set.clone(), 0.75).soundly_contains(1.0));
+		assert!(!sum.soundly_contains(Real::NAN));
 	}
 
 	#[test]
-	fn test_neg_inf_add()
+	fn test_abs_add()
 	{
-		let expected = ",1,10,100,1000,10000,100000,\n\
-						1,1,111111,111111,111111,111111,111111,\n\
-						10,111111,10,10,10,10,111110,\n\
-						100,111111,10,10,10,100,111000,\n\
-						1000,111111,10,10,1110,1000,111000,\n\
-						10000,111111,10,100,1000,10000,100000,\n\
-						100000,111111,111110,111000,111000,100000,100000,\n\
-						";
+		let set = Rc::new(RefCell::new(SornSet::new(-2.0, 2.0, 0.5, true)));
 
-		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 0.0, 1.0, true)));
-		let sorn1 = Sorn::new(set.clone());
-		let table = sorntable_gen::gen_table(sorn1.sorn_set.clone(), "add");
+		for (x, y) in [(-0.25, 0.75), (-1.5, -0.5), (1.0, -1.75), (0.0, 1.25), (-3.0, 0.5)]
+		{
+			let a = Sorn::from_f64(set.clone(), x);
+			let b = Sorn::from_f64(set.clone(), y);
 
-		println!("{}", table.to_csv());
-		println!("{}", expected);
+			let fused = a.abs_add(&b).unwrap();
+			let three_step = a.clone().abs() + b.clone().abs();
 
-		assert_eq!(table.to_csv(), expected);
+			assert_eq!(fused, three_step, "|{}| + |{}|", x, y);
+			assert!(fused.soundly_contains(x.abs() + y.abs()));
+		}
+
+		let inf = Sorn::from_f64(set.clone(), Real::INFINITY);
+		let one = Sorn::from_f64(set.clone(), 1.0);
+		assert_eq!(inf.abs_add(&one).unwrap(), inf);
+
+		let other = Sorn::from_f64(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))), 0.5);
+		assert!(matches!(one.abs_add(&other), Err(SornErrors::DifferentSornSets)));
+	}
+
+	#[test]
+	fn test_is_saturated()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, true)));
+		let inf = Sorn::from_f64(set.clone(), Real::INFINITY);
+		let zero = Sorn::from_f64(set.clone(), 0.0);
+		let half = Sorn::from_f64(set.clone(), 0.5);
+
+		/* A finite operand times [±inf] saturates if it may be 0, in either order */
+		assert!((&inf * &zero).is_saturated());
+		assert!((&zero * &inf).is_saturated());
+		assert!((&Sorn::from_interval(set.clone(), -0.5, 0.5, true, true) * &inf).is_saturated());
+		assert!(Sorn::full(set.clone()).is_saturated());
+
+		/* 0 / 0 is undefined as well */
+		assert!((&Sorn::from_interval(set.clone(), -0.5, 0.5, true, true) / &zero).is_saturated());
+
+		/* The other finite and [±inf] pairs are just [±inf] */
+		assert_eq!(&inf + &half, inf);
+		assert!(!(&inf + &half).is_saturated());
+		assert!(!(&inf * &half).is_saturated());
+		assert!(!(&inf - &inf).is_saturated());
+		assert!(!(&half + &half).is_saturated());
+		assert!(!Sorn::empty(set.clone()).is_saturated());
+		assert!(!Sorn::default().is_saturated());
+	}
+
+	#[test]
+	fn test_minkowski_sum()
+	{
+		/* [±inf], (-inf,0), [0], (0,1), [1], (1,2), [2], (2,3), [3], (3,inf) */
+		let set = Rc::new(RefCell::new(SornSet::new(0.0, 3.0, 1.0, true)));
+		let a = Sorn::from_scalars(set.clone(), &[0.0, 2.0]);
+		let b = Sorn::from_f64(set.clone(), 0.0);
+
+		let sum = &a + &b;
+		let hull = a.minkowski_sum(&b).unwrap();
+
+		assert!(!sum.is_contiguous());
+		assert!(hull.is_contiguous());
+		assert_eq!(hull.get_ranges().sets, vec![Exact(0.0), Open((0.0, 1.0)), Exact(1.0), Open((1.0, 2.0)), Exact(2.0)]);
+		assert_eq!(hull.intersect(&sum).unwrap(), sum);
+
+		/* [±inf] stays apart from the finite buckets */
+		let inf = Sorn::from_f64(set.clone(), Real::INFINITY);
+		let with_inf = a.union(&inf).unwrap().minkowski_sum(&b).unwrap();
+		assert_eq!(with_inf.bits, hull.bits | inf.bits);
+
+		let other = Sorn::from_f64(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))), 0.5);
+		assert!(matches!(a.minkowski_sum(&other), Err(SornErrors::DifferentSornSets)));
 	}
 }
\ No newline at end of file
@@ -1,24 +1,24 @@
-use std::{cell::RefCell, f64::{INFINITY, NEG_INFINITY}, rc::Rc};
-use std::hash::{DefaultHasher, Hash, Hasher};
-use fxhash::FxHashMap;
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+use fxhash::{FxHashMap, FxHashSet};
 
-use crate::sorn::{Sorn, SornBitsType};
+use crate::sorn::{sorn_max_bits, Op, Real, Sorn, SornBitsType, SornErrors};
+use crate::sorntable_gen::{gen_table, lookup_cells, SornTable};
 
 #[derive(Clone, Copy)]
 pub enum SornValue
 {
 	Empty,
-	Open((f64, f64)),
-	OpenLeft((f64, f64)),
-	OpenRight((f64, f64)),
-	Exact(f64),
+	Open((Real, Real)),
+	OpenLeft((Real, Real)),
+	OpenRight((Real, Real)),
+	Exact(Real),
 	PlusMinusInf,
 }
 
 impl SornValue
 {
 	/* Only works if the Value is exact, undefined for the rest */
-	pub fn get(&self) -> Option<f64>
+	pub fn get(&self) -> Option<Real>
 	{
 		match self
 		{
@@ -53,7 +53,7 @@ impl SornValue
 		}
 	}
 
-	pub fn min(&self) -> f64
+	pub fn min(&self) -> Real
 	{
 		match self
 		{
@@ -84,12 +84,12 @@ impl SornValue
 
 			SornValue::PlusMinusInf =>
 			{
-				NEG_INFINITY
+				Real::NEG_INFINITY
 			},
 		}
 	}
 
-	pub fn max(&self) -> f64
+	pub fn max(&self) -> Real
 	{
 		match self
 		{
@@ -120,7 +120,7 @@ impl SornValue
 
 			SornValue::PlusMinusInf =>
 			{
-				INFINITY
+				Real::INFINITY
 			},
 		}
 	}
@@ -156,6 +156,77 @@ impl SornValue
 	{
 		matches!(self, SornValue::PlusMinusInf)
 	}
+
+	/* Returns (min, min_closed, max, max_closed) of a bounded value */
+	pub(crate) fn closed_bounds(&self) -> Option<(Real, bool, Real, bool)>
+	{
+		match self
+		{
+			SornValue::Exact(v) => Some((*v, true, *v, true)),
+			SornValue::Open((start, end)) => Some((*start, false, *end, false)),
+			SornValue::OpenLeft((start, end)) => Some((*start, false, *end, true)),
+			SornValue::OpenRight((start, end)) => Some((*start, true, *end, false)),
+			SornValue::PlusMinusInf | SornValue::Empty => None,
+		}
+	}
+
+	/* Open ends exclude their endpoint, [±inf] only contains the infinities */
+	pub fn contains_point(&self, x: Real) -> bool
+	{
+		if self.is_pminf()
+		{
+			return x.is_infinite();
+		}
+
+		let Some((min, min_closed, max, max_closed)) = self.closed_bounds() else { return false; };
+
+		(min < x || (min_closed && min == x)) && (x < max || (max_closed && x == max))
+	}
+
+	/* True if every value of other is also in self, an empty other is in everything */
+	pub fn encloses(&self, other: &SornValue) -> bool
+	{
+		if self.is_pminf() || other.is_pminf()
+		{
+			return (other.is_pminf() && self.is_pminf()) || other.get().is_some_and(|x| self.contains_point(x));
+		}
+
+		let Some((b_min, b_min_closed, b_max, b_max_closed)) = other.closed_bounds() else { return *other == SornValue::Empty; };
+		let Some((a_min, a_min_closed, a_max, a_max_closed)) = self.closed_bounds() else { return false; };
+
+		(a_min < b_min || (a_min == b_min && (a_min_closed || !b_min_closed)))
+			&& (b_max < a_max || (b_max == a_max && (a_max_closed || !b_max_closed)))
+	}
+
+	/* True if both values contain at least one common real number, honoring open and closed ends */
+	pub fn overlaps(&self, other: &SornValue) -> bool
+	{
+		/* [±inf] holds exactly the infinities, the same as contains_point */
+		if self.is_pminf() || other.is_pminf()
+		{
+			let rest = if self.is_pminf() { other } else { self };
+
+			return rest.is_pminf() || rest.get().is_some_and(|x| x.is_infinite());
+		}
+
+		let Some((a_min, a_min_closed, a_max, a_max_closed)) = self.closed_bounds() else { return false; };
+		let Some((b_min, b_min_closed, b_max, b_max_closed)) = other.closed_bounds() else { return false; };
+
+		if [a_min, a_max, b_min, b_max].iter().any(|bound| bound.is_nan())
+		{
+			return false;
+		}
+
+		let (min, min_closed) = if a_min > b_min { (a_min, a_min_closed) }
+			else if b_min > a_min { (b_min, b_min_closed) }
+			else { (a_min, a_min_closed && b_min_closed) };
+
+		let (max, max_closed) = if a_max < b_max { (a_max, a_max_closed) }
+			else if b_max < a_max { (b_max, b_max_closed) }
+			else { (a_max, a_max_closed && b_max_closed) };
+
+		min < max || (min == max && min_closed && max_closed)
+	}
 }
 
 impl std::cmp::PartialEq for SornValue
@@ -175,6 +246,7 @@ impl std::cmp::PartialEq for SornValue
 impl std::cmp::Eq for SornValue
 {}
 
+/* -0.0 == 0.0 for PartialEq, so both hash as 0.0 (adding 0.0 turns -0.0 into 0.0) */
 impl std::hash::Hash for SornValue
 {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) 
@@ -190,26 +262,26 @@ impl std::hash::Hash for SornValue
 			Open((min, max)) =>
 			{
 				1u8.hash(state);
-				min.to_bits().hash(state);
-				max.to_bits().hash(state);
+				(min + 0.0).to_bits().hash(state);
+				(max + 0.0).to_bits().hash(state);
 			},
 			OpenLeft((min, max)) =>
 			{
 				2u8.hash(state);
-				min.to_bits().hash(state);
-				max.to_bits().hash(state);
+				(min + 0.0).to_bits().hash(state);
+				(max + 0.0).to_bits().hash(state);
 			},
 
 			OpenRight((min, max)) =>
 			{
 				3u8.hash(state);
-				min.to_bits().hash(state);
-				max.to_bits().hash(state);
+				(min + 0.0).to_bits().hash(state);
+				(max + 0.0).to_bits().hash(state);
 			},
 			Exact(val) =>
 			{
 				4u8.hash(state);
-				val.to_bits().hash(state);
+				(val + 0.0).to_bits().hash(state);
 			},
 			PlusMinusInf =>
 			{
@@ -281,55 +353,402 @@ impl std::fmt::Debug for SornValue
 	}
 }
 
+/*
+Same notation as Debug. A precision like {:.2} rounds the endpoints, a width pads
+the whole value with the fill and alignment of the formatter, left aligned by default.
+*/
 impl std::fmt::Display for SornValue
 {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
 	{
-		/* Use Debug Implementation */
-		write!(f, "{:?}", self)
+		let num = |x: &Real| match f.precision()
+		{
+			Some(precision) => format!("{:.*}", precision, x),
+			None => format!("{}", x),
+		};
+
+		let text = match self
+		{
+			SornValue::Open((start, end)) => format!("({},{})", num(start), num(end)),
+			SornValue::OpenLeft((start, end)) => format!("({},{}]", num(start), num(end)),
+			SornValue::OpenRight((start, end)) => format!("[{},{})", num(start), num(end)),
+			SornValue::Exact(value) => format!("[{}]", num(value)),
+			SornValue::Empty | SornValue::PlusMinusInf => format!("{:?}", self),
+		};
+
+		let pad = f.width().unwrap_or(0).saturating_sub(text.chars().count());
+		let (left, right) = match f.align()
+		{
+			Some(std::fmt::Alignment::Right) => (pad, 0),
+			Some(std::fmt::Alignment::Center) => (pad / 2, pad - pad / 2),
+			_ => (0, pad),
+		};
+		let fill = f.fill().to_string();
+
+		write!(f, "{}{}{}", fill.repeat(left), text, fill.repeat(right))
+	}
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SornValueParseError
+{
+	Empty,
+	MissingBrackets,
+	InvalidNumber,
+	InvalidFormat,
+	InvertedBounds,
+}
+
+/*
+Parses a single value in the same notation the Debug Implementation prints:
+[x] is Exact,
+(x,x) is Open,
+(x,x] is OpenLeft,
+[x,x) is OpenRight,
+[±inf] is PlusMinusInf
+*/
+impl TryFrom<&str> for SornValue
+{
+	type Error = SornValueParseError;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error>
+	{
+		let value = value.trim();
+
+		if value.is_empty()
+		{
+			return Err(SornValueParseError::Empty);
+		}
+
+		if value == "[±inf]"
+		{
+			return Ok(SornValue::PlusMinusInf);
+		}
+
+		let left_open = match value.chars().next()
+		{
+			Some('(') => true,
+			Some('[') => false,
+			_ => return Err(SornValueParseError::MissingBrackets),
+		};
+
+		let right_open = match value.chars().next_back()
+		{
+			Some(')') => true,
+			Some(']') => false,
+			_ => return Err(SornValueParseError::MissingBrackets),
+		};
+
+		/* Both brackets are single byte characters, so slicing is safe here */
+		if value.len() < 2
+		{
+			return Err(SornValueParseError::MissingBrackets);
+		}
+		let inner = &value[1..value.len()-1];
+		let numbers: Vec<&str> = inner.split(',').collect();
+
+		let mut parsed = Vec::with_capacity(numbers.len());
+		for number in &numbers
+		{
+			match number.trim().parse::<Real>()
+			{
+				Ok(number) => parsed.push(number),
+				Err(_) => return Err(SornValueParseError::InvalidNumber),
+			}
+		}
+
+		/* NaN is never a value, ±inf only as the open outer bound of the infinite buckets */
+		let valid = |(i, x): (usize, &Real)| x.is_finite() ||
+			(parsed.len() == 2 && i == 0 && left_open && *x == Real::NEG_INFINITY) ||
+			(parsed.len() == 2 && i == 1 && right_open && *x == Real::INFINITY);
+
+		if !parsed.iter().enumerate().all(valid)
+		{
+			return Err(SornValueParseError::InvalidNumber);
+		}
+
+		match (parsed.len(), left_open, right_open)
+		{
+			(1, false, false) => Ok(SornValue::Exact(parsed[0])),
+			(2, _, _) if parsed[0] > parsed[1] => Err(SornValueParseError::InvertedBounds),
+			(2, true, true) => Ok(SornValue::Open((parsed[0], parsed[1]))),
+			(2, true, false) => Ok(SornValue::OpenLeft((parsed[0], parsed[1]))),
+			(2, false, true) => Ok(SornValue::OpenRight((parsed[0], parsed[1]))),
+			/* Closed intervals and open single values have no SornValue representation */
+			_ => Err(SornValueParseError::InvalidFormat),
+		}
 	}
 }
 
 const MAX_SETS: usize = 128;
+/* Largest set all_sorns enumerates, 2^20 Sorns */
+pub const MAX_ENUMERATED: usize = 20;
+/* Largest set check_commutative takes, 2^20 pairs of Sorns */
+pub const MAX_COMMUTATIVE_CHECKED: usize = 10;
+/* Largest set check_associative takes, 2^18 triples of Sorns */
+pub const MAX_ASSOCIATIVE_CHECKED: usize = 6;
+
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CacheCounter
+{
+	pub hits: u64,
+	pub misses: u64,
+	pub table_hits: u64,
+}
+
+/* Precompute cache hits and misses and attached table hits of every operation in checked_op */
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct OpMetrics
+{
+	pub add: CacheCounter,
+	pub sub: CacheCounter,
+	pub mul: CacheCounter,
+	pub div: CacheCounter,
+}
+
+/*
+Result cache of a single operation. Unbounded by default, with a capacity set
+the least recently used entries are evicted.
+*/
+#[derive(Clone)]
+pub struct OpCache<K>
+{
+	/* Value is (result Bits, tick of the last use) */
+	entries: FxHashMap<K, (SornBitsType, u64)>,
+	/* Keys ordered by the tick of their last use, the first one is evicted next */
+	recency: BTreeMap<u64, K>,
+	capacity: Option<usize>,
+	tick: u64,
+}
+
+impl<K: std::hash::Hash + Eq + Copy> Default for OpCache<K>
+{
+	fn default() -> Self
+	{
+		OpCache
+		{
+			entries: FxHashMap::default(),
+			recency: BTreeMap::new(),
+			capacity: None,
+			tick: 0,
+		}
+	}
+}
+
+impl<K: std::hash::Hash + Eq + Copy> OpCache<K>
+{
+	pub fn contains_key(&self, key: &K) -> bool
+	{
+		self.entries.contains_key(key)
+	}
+
+	/* Marks the entry as most recently used */
+	pub fn get(&mut self, key: &K) -> Option<SornBitsType>
+	{
+		let (bits, last_used) = self.entries.get_mut(key)?;
+
+		self.tick += 1;
+		self.recency.remove(last_used);
+		self.recency.insert(self.tick, *key);
+		*last_used = self.tick;
+
+		return Some(*bits);
+	}
+
+	pub fn insert(&mut self, key: K, bits: SornBitsType)
+	{
+		self.tick += 1;
+
+		if let Some((_, last_used)) = self.entries.insert(key, (bits, self.tick))
+		{
+			self.recency.remove(&last_used);
+		}
+
+		self.recency.insert(self.tick, key);
+		self.evict();
+	}
+
+	pub fn len(&self) -> usize
+	{
+		self.entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		self.entries.is_empty()
+	}
+
+	pub fn clear(&mut self)
+	{
+		self.entries.clear();
+		self.recency.clear();
+	}
+
+	pub fn set_capacity(&mut self, capacity: Option<usize>)
+	{
+		self.capacity = capacity;
+		self.evict();
+	}
+
+	fn evict(&mut self)
+	{
+		let Some(capacity) = self.capacity else { return; };
+
+		while self.entries.len() > capacity
+		{
+			let Some((_, oldest)) = self.recency.pop_first() else { return; };
+			self.entries.remove(&oldest);
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GapKind
+{
+	Open,
+	OpenLeft,
+	OpenRight,
+}
+
+/*
+How SornSet::new_with_style fills the grid. Without exact_points the grid points
+are only covered by the closed ends of the gaps, so with Open gaps they are not
+representable at all. The one grid end no half open gap closes gets an exact point.
+With exact_points every gap is Open, half open gaps would overlap the exact points.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridStyle
+{
+	pub gaps: GapKind,
+	pub exact_points: bool,
+}
+
+impl Default for GridStyle
+{
+	fn default() -> Self
+	{
+		GridStyle
+		{
+			gaps: GapKind::Open,
+			exact_points: true,
+		}
+	}
+}
 
 #[derive(Clone)]
 pub struct SornSet
 {
-	/* Key is own Bits, Value is result Bits */
-	pub precomputed_pow: FxHashMap<SornBitsType, SornBitsType>,
+	/* Key is (own Bits, power), Value is result Bits */
+	pub precomputed_pow: OpCache<(SornBitsType, i32)>,
 
 	/* Key is (self.bits, operand.bits), Value is result Bits */
-	pub precomputed_add: FxHashMap<(SornBitsType, SornBitsType), SornBitsType>,
-	pub precomputed_sub: FxHashMap<(SornBitsType, SornBitsType), SornBitsType>,
-	pub precomputed_mul: FxHashMap<(SornBitsType, SornBitsType), SornBitsType>,
-	pub precomputed_div: FxHashMap<(SornBitsType, SornBitsType), SornBitsType>,
+	pub precomputed_add: OpCache<(SornBitsType, SornBitsType)>,
+	pub precomputed_sub: OpCache<(SornBitsType, SornBitsType)>,
+	pub precomputed_mul: OpCache<(SornBitsType, SornBitsType)>,
+	pub precomputed_div: OpCache<(SornBitsType, SornBitsType)>,
+
+	/* Result bits of every single element pair, see attach_table */
+	attached_tables: FxHashMap<Op, Vec<Vec<SornBitsType>>>,
 
 	pub sets: Vec<SornValue>,
-	pub contains_inf: bool,
+	/* Use set_contains_inf to change it, the infinity buckets depend on it */
+	pub(crate) contains_inf: bool,
 	pub one_bit: SornBitsType,
+	pub zero_bit: SornBitsType,
+
+	#[cfg(feature = "metrics")]
+	metrics: OpMetrics,
 }
 
-impl SornSet
+impl Default for SornSet
 {
-	pub fn default() -> Self
+	fn default() -> Self
 	{
 		SornSet
 		{
-			precomputed_pow: FxHashMap::default(),
+			precomputed_pow: OpCache::default(),
 			
-			precomputed_add: FxHashMap::default(),
-			precomputed_sub: FxHashMap::default(),
-			precomputed_mul: FxHashMap::default(),
-			precomputed_div: FxHashMap::default(),
+			precomputed_add: OpCache::default(),
+			precomputed_sub: OpCache::default(),
+			precomputed_mul: OpCache::default(),
+			precomputed_div: OpCache::default(),
+
+			attached_tables: FxHashMap::default(),
 
 			sets: Vec::with_capacity(MAX_SETS),
 			contains_inf: false,
 			one_bit: 0,
+			zero_bit: 0,
+
+			#[cfg(feature = "metrics")]
+			metrics: OpMetrics::default(),
+		}
+	}
+}
+
+impl SornSet
+{
+	/*
+	With has_inf the set is [±inf], (-inf, start), ..., (end, inf). [±inf] only holds the
+	infinities themselves, the open tails hold the finite values outside start..end.
+	Panics if step is not positive, end is not above start or the set needs more elements
+	than a Sorn has bits, use try_new to handle this
+	*/
+	pub fn new(start: Real, end: Real, step: Real, has_inf: bool) -> Self
+	{
+		SornSet::new_with_style(start, end, step, has_inf, GridStyle::default())
+	}
+
+	pub fn try_new(start: Real, end: Real, step: Real, has_inf: bool) -> Result<Self, SornErrors>
+	{
+		SornSet::check_grid(start, end, step, has_inf, GridStyle::default())?;
+
+		Ok(SornSet::new(start, end, step, has_inf))
+	}
+
+	fn check_range(start: Real, end: Real, step: Real) -> Result<(), SornErrors>
+	{
+		if start.is_nan() || end.is_nan() || step.is_nan() || step <= 0.0 || end <= start
+		{
+			return Err(SornErrors::InvalidRange);
 		}
+
+		Ok(())
+	}
+
+	/* check_range, plus the number of elements new_with_style would push */
+	fn check_grid(start: Real, end: Real, step: Real, has_inf: bool, style: GridStyle) -> Result<(), SornErrors>
+	{
+		SornSet::check_range(start, end, step)?;
+
+		let gaps = ((end - start) / step).floor();
+		let exacts = if style.exact_points { gaps + 1.0 } else if style.gaps == GapKind::Open { 0.0 } else { 1.0 };
+		let infs = if has_inf { 3.0 } else { 0.0 };
+
+		/* Counted as floats, an infinite range must not overflow the count */
+		if gaps + exacts + infs > sorn_max_bits() as Real
+		{
+			return Err(SornErrors::SetTooLarge);
+		}
+
+		Ok(())
 	}
 
-	pub fn new(start: f64, end: f64, step: f64, has_inf: bool) -> Self
+	/*
+	Like new, but with the kind of the gaps and the exact grid points chosen by style.
+	Panics on the same input as new
+	*/
+	pub fn new_with_style(start: Real, end: Real, step: Real, has_inf: bool, style: GridStyle) -> Self
 	{
+		match SornSet::check_grid(start, end, step, has_inf, style)
+		{
+			Ok(()) => (),
+			Err(SornErrors::SetTooLarge) => panic!("Tried to create a SornSet with more elements than fit into a Sorn"),
+			Err(_) => panic!("Tried to create a SornSet with end <= start or step <= 0"),
+		}
+
 		/* Calculate number of ranges and bit size of sorn */
 		let num_sets = ((end - start) / step).floor() as u32;
 
@@ -339,31 +758,146 @@ impl SornSet
 		if has_inf 
 		{
 			sets.contains_inf = true;
-			//sets.push(SornValue::PlusMinusInf);
-			sets.push(SornValue::Open((f64::NEG_INFINITY, start)));
+			sets.push(SornValue::PlusMinusInf);
+			sets.push(SornValue::Open((Real::NEG_INFINITY, start)));
+		}
+
+		let gaps = if style.exact_points { GapKind::Open } else { style.gaps };
+
+		if style.exact_points || gaps == GapKind::OpenLeft
+		{
+			sets.push(SornValue::Exact(start));
 		}
 
 		for i in 0..num_sets
 		{
-			let first = (i as f64 * step) + start;
+			let first = (i as Real * step) + start;
 			let second = first + step;
 
-			sets.push(SornValue::Exact(first));
-			sets.push(SornValue::Open((first, second)));
+			if style.exact_points && i > 0
+			{
+				sets.push(SornValue::Exact(first));
+			}
+
+			match gaps
+			{
+				GapKind::Open => sets.push(SornValue::Open((first, second))),
+				GapKind::OpenLeft => sets.push(SornValue::OpenLeft((first, second))),
+				GapKind::OpenRight => sets.push(SornValue::OpenRight((first, second))),
+			}
+		}
+
+		if style.exact_points || gaps == GapKind::OpenRight
+		{
+			sets.push(SornValue::Exact(end));
 		}
-		sets.push(SornValue::Exact(end));
 
 		if has_inf 
 		{
-			sets.push(SornValue::Open((end, f64::INFINITY)));
+			sets.push(SornValue::Open((end, Real::INFINITY)));
 		}
 
-		let one_bit = Sorn::sorn_to_bits(Rc::new(RefCell::new(sets.clone())), &SornValue::Exact(1.0));
-		sets.one_bit = one_bit;
+		sets.recompute();
 
 		sets
 	}
 
+	/*
+	Set over [-bound, bound] with exact points at the multiples of step and at ±bound, built
+	outward from an exact [0] so it is symmetric even if bound is no multiple of step.
+	Panics if step or bound is not positive or the set needs more elements than a Sorn has bits,
+	use try_new_symmetric to handle this
+	*/
+	pub fn new_symmetric(bound: Real, step: Real, has_inf: bool) -> Self
+	{
+		match SornSet::try_new_symmetric(bound, step, has_inf)
+		{
+			Ok(set) => set,
+			Err(SornErrors::SetTooLarge) => panic!("Tried to create a symmetric SornSet with more elements than fit into a Sorn"),
+			Err(_) => panic!("Tried to create a symmetric SornSet with bound <= 0 or step <= 0"),
+		}
+	}
+
+	pub fn try_new_symmetric(bound: Real, step: Real, has_inf: bool) -> Result<Self, SornErrors>
+	{
+		if !bound.is_finite()
+		{
+			return Err(SornErrors::InvalidRange);
+		}
+
+		SornSet::check_range(-bound, bound, step)?;
+
+		/* Stop early, a side with sorn_max_bits points can never fit */
+		let mut positive: Vec<Real> = (1..).map(|i| i as Real * step).take_while(|x| *x < bound).take(sorn_max_bits()).collect();
+
+		if positive.len() == sorn_max_bits()
+		{
+			return Err(SornErrors::SetTooLarge);
+		}
+
+		positive.push(bound);
+
+		let points: Vec<Real> = positive.iter().rev().map(|x| -x).chain(std::iter::once(0.0)).chain(positive.iter().copied()).collect();
+
+		SornSet::try_from_breakpoints(&points, has_inf)
+	}
+
+	/*
+	General case of new for sorted breakpoints with any spacing, every breakpoint
+	becomes an exact point with an open gap to the next one.
+	Panics if points is empty, unsorted, has duplicates or needs more elements than a Sorn
+	has bits, use try_from_breakpoints to handle this
+	*/
+	pub fn from_breakpoints(points: &[Real], has_inf: bool) -> Self
+	{
+		match SornSet::try_from_breakpoints(points, has_inf)
+		{
+			Ok(set) => set,
+			Err(SornErrors::SetTooLarge) => panic!("Tried to create a SornSet from more breakpoints than fit into a Sorn"),
+			Err(_) => panic!("Tried to create a SornSet from empty, unsorted or duplicate breakpoints"),
+		}
+	}
+
+	pub fn try_from_breakpoints(points: &[Real], has_inf: bool) -> Result<Self, SornErrors>
+	{
+		if points.is_empty() || points.iter().any(|point| !point.is_finite()) || points.windows(2).any(|pair| pair[0] >= pair[1])
+		{
+			return Err(SornErrors::InvalidRange);
+		}
+
+		/* An exact point per breakpoint with a gap in between, plus [±inf] and both infinite gaps */
+		if 2 * points.len() - 1 + if has_inf { 3 } else { 0 } > sorn_max_bits()
+		{
+			return Err(SornErrors::SetTooLarge);
+		}
+
+		let mut sets = SornSet::default();
+
+		if has_inf
+		{
+			sets.contains_inf = true;
+			sets.push(SornValue::PlusMinusInf);
+			sets.push(SornValue::Open((Real::NEG_INFINITY, points[0])));
+		}
+
+		for pair in points.windows(2)
+		{
+			sets.push(SornValue::Exact(pair[0]));
+			sets.push(SornValue::Open((pair[0], pair[1])));
+		}
+
+		sets.push(SornValue::Exact(points[points.len()-1]));
+
+		if has_inf
+		{
+			sets.push(SornValue::Open((points[points.len()-1], Real::INFINITY)));
+		}
+
+		sets.recompute();
+
+		Ok(sets)
+	}
+
 	/* 
 	[x] is Exact,  
 	(x, x) is Open,
@@ -423,61 +957,531 @@ impl SornSet
 			}
 		}
 
-		let one_bit = Sorn::sorn_to_bits(Rc::new(RefCell::new(sets.clone())), &SornValue::Exact(1.0));
-		sets.one_bit = one_bit;
+		sets.recompute();
 
 		sets
 	}
 
-	pub fn len(&self) -> usize
+	/* Like from_string, but normalizes the elements, so they may be listed in any order */
+	pub fn from_string_sorted(string: &str) -> Self
 	{
-		self.sets.len()
+		let mut sets = SornSet::from_string(string);
+		sets.normalize();
+
+		sets
 	}
 
-	pub fn first(&self) -> Option<&SornValue>
+	/* Number of entries across all precompute caches */
+	pub fn cache_len(&self) -> usize
 	{
-		if self.len() > 0
-		{
-			Some(&self.sets[0])
-		}
-		else
-		{
-			None
-		}
+		self.precomputed_pow.len() +
+		self.precomputed_add.len() +
+		self.precomputed_sub.len() +
+		self.precomputed_mul.len() +
+		self.precomputed_div.len()
 	}
 
-	pub fn last(&self) -> Option<&SornValue>
+	/* Rough estimate only, counts the stored keys and values but not the hashmap and tree overhead */
+	pub fn cache_memory_bytes(&self) -> usize
 	{
-		if self.len() > 0
-		{
-			Some(&self.sets[self.len()-1])
-		}
-		else
-		{
-			None
-		}
+		/* Every entry is stored once in the hashmap and once more as (tick, key) in the recency index */
+		let unary_entry = std::mem::size_of::<((SornBitsType, i32), (SornBitsType, u64))>() + std::mem::size_of::<(u64, (SornBitsType, i32))>();
+		let binary_entry = std::mem::size_of::<((SornBitsType, SornBitsType), (SornBitsType, u64))>() + std::mem::size_of::<(u64, (SornBitsType, SornBitsType))>();
+
+		self.precomputed_pow.len() * unary_entry +
+		(self.cache_len() - self.precomputed_pow.len()) * binary_entry
 	}
 
-	pub fn is_empty(&self) -> bool
+	/* Limits every operation cache to cap entries, evicting the least recently used ones */
+	pub fn set_cache_capacity(&mut self, cap: usize)
 	{
-		self.len() == 0
+		self.precomputed_pow.set_capacity(Some(cap));
+		self.precomputed_add.set_capacity(Some(cap));
+		self.precomputed_sub.set_capacity(Some(cap));
+		self.precomputed_mul.set_capacity(Some(cap));
+		self.precomputed_div.set_capacity(Some(cap));
 	}
 
+	pub fn clear_caches(&mut self)
+	{
+		self.precomputed_pow.clear();
+		self.precomputed_add.clear();
+		self.precomputed_sub.clear();
+		self.precomputed_mul.clear();
+		self.precomputed_div.clear();
+		self.attached_tables.clear();
+	}
+
+	/*
+	Lets checked_op answer op from a table generated by gen_table instead of combining
+	every pair of ranges. The table has to be generated from this set, it is dropped
+	with the caches once the elements change.
+	*/
+	pub fn attach_table(&mut self, op: Op, table: &SornTable) -> Result<(), SornErrors>
+	{
+		if table.rows() != self.len() || table.columns() != self.len()
+		{
+			return Err(SornErrors::DimensionMismatch);
+		}
+
+		self.attached_tables.insert(op, table.data().to_vec());
+
+		Ok(())
+	}
+
+	/* Result of lhs op rhs from an attached table, None if there is no table for op */
+	pub fn table_lookup(&self, op: Op, lhs: SornBitsType, rhs: SornBitsType) -> Option<SornBitsType>
+	{
+		let data = self.attached_tables.get(&op)?;
+
+		Some(lookup_cells(data, lhs, rhs))
+	}
+
+	#[cfg(feature = "metrics")]
+	pub fn metrics(&self) -> OpMetrics
+	{
+		self.metrics
+	}
+
+	#[cfg(feature = "metrics")]
+	pub(crate) fn record_cache_lookup(&mut self, operation: &str, hit: bool)
+	{
+		let counter = match operation
+		{
+			"add" => &mut self.metrics.add,
+			"sub" => &mut self.metrics.sub,
+			"mul" => &mut self.metrics.mul,
+			"div" => &mut self.metrics.div,
+			_ => return,
+		};
+
+		if hit
+		{
+			counter.hits += 1;
+		}
+		else
+		{
+			counter.misses += 1;
+		}
+	}
+
+	#[cfg(not(feature = "metrics"))]
+	pub(crate) fn record_cache_lookup(&mut self, _operation: &str, _hit: bool)
+	{}
+
+	#[cfg(feature = "metrics")]
+	pub(crate) fn record_table_hit(&mut self, operation: &str)
+	{
+		match operation
+		{
+			"add" => self.metrics.add.table_hits += 1,
+			"sub" => self.metrics.sub.table_hits += 1,
+			"mul" => self.metrics.mul.table_hits += 1,
+			"div" => self.metrics.div.table_hits += 1,
+			_ => (),
+		}
+	}
+
+	#[cfg(not(feature = "metrics"))]
+	pub(crate) fn record_table_hit(&mut self, _operation: &str)
+	{}
+
+	pub fn contains_inf(&self) -> bool
+	{
+		self.contains_inf
+	}
+
+	/*
+	Adds or removes [±inf] and the two infinite tails around the finite elements.
+	Bit positions change, so one_bit and zero_bit are recomputed and the caches are cleared.
+	*/
+	pub fn set_contains_inf(&mut self, contains_inf: bool)
+	{
+		if contains_inf == self.contains_inf
+		{
+			return;
+		}
+
+		let finite: Vec<SornValue> = self.finite_elements().copied().collect();
+
+		let lo = finite.iter().map(|item| item.min()).reduce(Real::min);
+		let hi = finite.iter().map(|item| item.max()).reduce(Real::max);
+
+		self.sets.clear();
+
+		if contains_inf
+		{
+			self.push(SornValue::PlusMinusInf);
+
+			if let Some(lo) = lo
+			{
+				self.push(SornValue::Open((Real::NEG_INFINITY, lo)));
+			}
+		}
+
+		for item in finite
+		{
+			self.push(item);
+		}
+
+		if contains_inf && let Some(hi) = hi
+		{
+			self.push(SornValue::Open((hi, Real::INFINITY)));
+		}
+
+		self.contains_inf = contains_inf;
+		self.recompute();
+	}
+
+	/* Bits of [±inf] and of the tails reaching to -inf or inf */
+	pub fn inf_bits(&self) -> SornBitsType
+	{
+		let mut bits = 0;
+
+		for (i, item) in self.sets.iter().enumerate()
+		{
+			if item.is_pminf() || item.min().is_infinite() || item.max().is_infinite()
+			{
+				bits |= 1 << i;
+			}
+		}
+
+		return bits;
+	}
+
+	pub fn len(&self) -> usize
+	{
+		self.sets.len()
+	}
+
+	pub fn distinct_count(&self) -> usize
+	{
+		self.len()
+	}
+
+	/* Number of Exact elements, the open gaps between them are not counted */
+	pub fn real_point_count(&self) -> usize
+	{
+		self.sets.iter().filter(|item| item.is_exact()).count()
+	}
+
+	/*
+	Snaps x to the nearest Exact element, None if the set has no exact points.
+	When x lies exactly between two points the lower one is returned.
+	*/
+	pub fn quantize(&self, x: Real) -> Option<Real>
+	{
+		let nearest = self.sets.iter()
+			.filter_map(|item| item.get())
+			.min_by(|a, b| (a - x).abs().total_cmp(&(b - x).abs()).then(a.total_cmp(b)));
+
+		return nearest;
+	}
+
+	/* Largest exact point <= x, None if every exact point is above x */
+	pub fn floor_exact(&self, x: Real) -> Option<Real>
+	{
+		return self.sets.iter().filter_map(|item| item.get()).filter(|point| *point <= x).reduce(Real::max);
+	}
+
+	/* Smallest exact point >= x, None if every exact point is below x */
+	pub fn ceil_exact(&self, x: Real) -> Option<Real>
+	{
+		return self.sets.iter().filter_map(|item| item.get()).filter(|point| *point >= x).reduce(Real::min);
+	}
+
+	pub fn first(&self) -> Option<&SornValue>
+	{
+		if !self.is_empty()
+		{
+			Some(&self.sets[0])
+		}
+		else
+		{
+			None
+		}
+	}
+
+	pub fn last(&self) -> Option<&SornValue>
+	{
+		if !self.is_empty()
+		{
+			Some(&self.sets[self.len()-1])
+		}
+		else
+		{
+			None
+		}
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		self.len() == 0
+	}
+
+	/* Panics if the set would no longer fit into SornBitsType, use try_push to handle this */
 	pub fn push(&mut self, item: SornValue)
 	{
+		if self.try_push(item).is_err()
+		{
+			panic!("Tried to push more than {} elements into a SornSet, change SornBitsType if u need more bits", sorn_max_bits());
+		}
+	}
+
+	pub fn try_push(&mut self, item: SornValue) -> Result<(), SornErrors>
+	{
+		if self.len() >= sorn_max_bits()
+		{
+			return Err(SornErrors::SetTooLarge);
+		}
+
 		self.sets.push(item);
+
+		Ok(())
+	}
+
+	/* Removes repeated elements, keeping the first one. Bit positions change, so the caches are cleared */
+	pub fn dedup(&mut self)
+	{
+		let mut seen: FxHashSet<SornValue> = FxHashSet::default();
+		let len = self.len();
+
+		self.sets.retain(|item| seen.insert(*item));
+
+		if self.len() != len
+		{
+			self.recompute();
+		}
+	}
+
+	/* True if op maps every pair of single elements onto at least one element, so nothing is lost out of range */
+	pub fn is_closed_under(&self, op: Op) -> bool
+	{
+		let table = gen_table(Rc::new(RefCell::new(self.clone())), op.name());
+
+		table.iter_cells().all(|(_, _, bits)| bits != 0)
+	}
+
+	/* Call recompute after changing elements through this */
+	pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut SornValue>
+	{
+		self.sets.iter_mut()
+	}
+
+	/* Refreshes one_bit and zero_bit and clears the caches after the elements were changed in place */
+	pub fn recompute(&mut self)
+	{
+		self.one_bit = Sorn::sorn_to_bits(Rc::new(RefCell::new(self.clone())), &SornValue::Exact(1.0));
+		self.zero_bit = Sorn::sorn_to_bits(Rc::new(RefCell::new(self.clone())), &SornValue::Exact(0.0));
+		self.clear_caches();
+	}
+
+	/*
+	New set with every endpoint mapped by x -> scale * x + offset, a negative scale
+	mirrors the set so the open sides and the element order are flipped as well.
+	Panics if scale is 0 or scale or offset is not finite, that would collapse the buckets
+	*/
+	pub fn affine(&self, scale: Real, offset: Real) -> SornSet
+	{
+		if scale == 0.0 || !scale.is_finite() || !offset.is_finite()
+		{
+			panic!("Tried to map a SornSet with a zero or non-finite scale or a non-finite offset");
+		}
+
+		let mut result = self.clone();
+		let map = |x: Real| scale * x + offset;
+
+		for item in result.iter_mut()
+		{
+			let (start, end) = (map(item.min()), map(item.max()));
+
+			*item = match (*item, scale < 0.0)
+			{
+				(SornValue::PlusMinusInf, _) | (SornValue::Empty, _) => *item,
+				(SornValue::Exact(_), _) => SornValue::Exact(start),
+				(SornValue::Open(_), false) => SornValue::Open((start, end)),
+				(SornValue::Open(_), true) => SornValue::Open((end, start)),
+				(SornValue::OpenLeft(_), false) => SornValue::OpenLeft((start, end)),
+				(SornValue::OpenLeft(_), true) => SornValue::OpenRight((end, start)),
+				(SornValue::OpenRight(_), false) => SornValue::OpenRight((start, end)),
+				(SornValue::OpenRight(_), true) => SornValue::OpenLeft((end, start)),
+			};
+		}
+
+		if scale < 0.0
+		{
+			/* [±inf] stays in front */
+			let first = result.sets.iter().take_while(|item| item.is_pminf()).count();
+			result.sets[first..].reverse();
+		}
+
+		result.recompute();
+
+		return result;
+	}
+
+	/*
+	Higher resolution set aligned with this one, every finite endpoint stays an exact point and
+	each gap between two of them is split into factor equal parts. [±inf] and the infinite
+	buckets are kept if the set has them. Gaps the set does not cover are filled as well.
+	Panics if factor is 0, the set has no finite endpoint or the refined set needs more
+	elements than a Sorn has bits
+	*/
+	pub fn refined_like(&self, factor: usize) -> SornSet
+	{
+		if factor == 0
+		{
+			panic!("Tried to refine a SornSet with factor 0");
+		}
+
+		let mut points: Vec<Real> = self.sets.iter()
+			.flat_map(|item| [item.min(), item.max()])
+			.filter(|x| x.is_finite())
+			.collect();
+		points.sort_by(|a, b| a.total_cmp(b));
+		points.dedup();
+
+		/* Every breakpoint is an element, so checking their count first keeps a huge factor from allocating */
+		if points.len().saturating_sub(1).saturating_mul(factor) >= sorn_max_bits()
+		{
+			panic!("Tried to refine a SornSet into more elements than fit into a Sorn");
+		}
+
+		let mut refined = Vec::with_capacity(points.len() * factor);
+
+		for pair in points.windows(2)
+		{
+			let step = (pair[1] - pair[0]) / factor as Real;
+			refined.push(pair[0]);
+			refined.extend((1..factor).map(|i| pair[0] + i as Real * step).filter(|x| *x > pair[0] && *x < pair[1]));
+		}
+
+		if let Some(last) = points.last()
+		{
+			refined.push(*last);
+		}
+
+		SornSet::from_breakpoints(&refined, self.contains_inf)
+	}
+
+	/*
+	Every one of the 2^len Sorns over set, for exhaustive checks of operation laws.
+	Fails with SetTooLarge for sets with more than MAX_ENUMERATED elements.
+	*/
+	pub fn all_sorns(set: Rc<RefCell<SornSet>>) -> Result<impl Iterator<Item = Sorn>, SornErrors>
+	{
+		let len = set.borrow().len();
+
+		if len > MAX_ENUMERATED
+		{
+			return Err(SornErrors::SetTooLarge);
+		}
+
+		return Ok((0..(1 as SornBitsType) << len).map(move |bits|
+		{
+			let mut sorn = Sorn::new(set.clone());
+			sorn.bits = bits;
+			sorn
+		}));
+	}
+
+	/*
+	Whether a op b == b op a for every pair of Sorns over this set.
+	Fails with SetTooLarge for sets with more than MAX_COMMUTATIVE_CHECKED elements.
+	*/
+	pub fn check_commutative(&self, op: Op) -> Result<bool, SornErrors>
+	{
+		if self.len() > MAX_COMMUTATIVE_CHECKED
+		{
+			return Err(SornErrors::SetTooLarge);
+		}
+
+		let set = Rc::new(RefCell::new(self.clone()));
+		let all: Vec<Sorn> = SornSet::all_sorns(set)?.collect();
+
+		return Ok(all.iter().all(|a| all.iter().all(|b| apply_op(a, b, op) == apply_op(b, a, op))));
+	}
+
+	/*
+	Every (a, b, c) as bits where (a op b) op c != a op (b op c). The projection after each
+	step makes even add fail for some triples. The work grows with 8^len, so this fails with
+	SetTooLarge for sets with more than MAX_ASSOCIATIVE_CHECKED elements.
+	*/
+	pub fn check_associative(&self, op: Op) -> Result<Vec<(SornBitsType, SornBitsType, SornBitsType)>, SornErrors>
+	{
+		if self.len() > MAX_ASSOCIATIVE_CHECKED
+		{
+			return Err(SornErrors::SetTooLarge);
+		}
+
+		let set = Rc::new(RefCell::new(self.clone()));
+		let all: Vec<Sorn> = SornSet::all_sorns(set)?.collect();
+		let mut broken = Vec::new();
+
+		for a in &all
+		{
+			for b in &all
+			{
+				let ab = apply_op(a, b, op);
+
+				for c in &all
+				{
+					if apply_op(&ab, c, op) != apply_op(a, &apply_op(b, c, op), op)
+					{
+						broken.push((a.bits, b.bits, c.bits));
+					}
+				}
+			}
+		}
+
+		return Ok(broken);
+	}
+
+	/* Sorts the elements, [±inf] first, and removes duplicates */
+	pub fn normalize(&mut self)
+	{
+		self.normalize_eps(0.0);
+	}
+
+	/*
+	Like normalize, but elements of the same kind whose endpoints all differ by at
+	most eps count as duplicates, so float noise like 0.1 + 0.2 != 0.3 is merged.
+	The first element of each group after sorting is kept.
+	*/
+	pub fn normalize_eps(&mut self, eps: Real)
+	{
+		self.sets.sort_by(|a, b| b.is_pminf().cmp(&a.is_pminf())
+			.then(a.min().total_cmp(&b.min()))
+			.then(a.max().total_cmp(&b.max())));
+
+		self.sets.dedup_by(|b, a| std::mem::discriminant(a) == std::mem::discriminant(b)
+			&& (a.is_pminf() || ((a.min() - b.min()).abs() <= eps && (a.max() - b.max()).abs() <= eps)));
+
+		self.recompute();
+	}
+
+	/* Every element except [±inf] and the tails reaching to infinity */
+	fn finite_elements(&self) -> impl Iterator<Item = &SornValue>
+	{
+		self.sets.iter().filter(|item| !item.is_pminf() && item.min().is_finite() && item.max().is_finite())
+	}
+
+	/* Equality of the finite buckets only, so a set matches its version with infinity */
+	pub fn same_finite_structure(&self, other: &Self) -> bool
+	{
+		self.finite_elements().eq(other.finite_elements())
 	}
 
 	pub fn get(&self, index: usize) -> SornValue
 	{
-		self.sets[index].clone()
+		self.sets[index]
 	}
 
 	pub fn get_min_range(&self) -> Option<SornValue>
 	{
 		let ranges = &self.sets;
 
-		if self.len() == 0
+		if self.is_empty()
 		{
 			return None;
 		}
@@ -489,7 +1493,7 @@ impl SornSet
 	{
 		let ranges = &self.sets;
 
-		if ranges.len() == 0
+		if ranges.is_empty()
 		{
 			return None;
 		}
@@ -507,7 +1511,7 @@ impl SornSet
 			(range.is_interval() && item.is_exact()) && (range.min() <= item.get().unwrap() && range.max() >= item.get().unwrap()) ||
 			(item.is_pminf() && range.is_pminf() && self.contains_inf)
 			{
-				result.push(item.clone());
+				result.push(*item);
 			}
 		}
 		
@@ -515,6 +1519,17 @@ impl SornSet
 	}
 }
 
+fn apply_op(a: &Sorn, b: &Sorn, op: Op) -> Sorn
+{
+	match op
+	{
+		Op::Add => a + b,
+		Op::Sub => a - b,
+		Op::Mul => a * b,
+		Op::Div => a / b,
+	}
+}
+
 impl std::cmp::PartialEq for SornSet
 {
 	fn eq(&self, other: &Self) -> bool 
@@ -523,6 +1538,79 @@ impl std::cmp::PartialEq for SornSet
 	}
 }
 
+impl std::cmp::Eq for SornSet
+{}
+
+/* Only the elements and contains_inf, like PartialEq, the caches are ignored */
+impl std::hash::Hash for SornSet
+{
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H)
+	{
+		self.sets.hash(state);
+		self.contains_inf.hash(state);
+	}
+}
+
+/*
+Hands out one shared set per distinct structure, so Sorns built on equal sets also
+share their caches. Sets must not be restructured after interning, else an equal
+set interned later gets its own instance.
+*/
+#[derive(Default)]
+pub struct SornSetRegistry
+{
+	/* Index into sets is the id of a set */
+	sets: Vec<Rc<RefCell<SornSet>>>,
+	ids: FxHashMap<(Vec<SornValue>, bool), usize>,
+}
+
+impl SornSetRegistry
+{
+	pub fn new() -> Self
+	{
+		SornSetRegistry::default()
+	}
+
+	/* The shared instance equal to set, set itself becomes it if there is none yet */
+	pub fn intern(&mut self, set: SornSet) -> Rc<RefCell<SornSet>>
+	{
+		let key = (set.sets.clone(), set.contains_inf);
+
+		if let Some(&id) = self.ids.get(&key)
+		{
+			return self.sets[id].clone();
+		}
+
+		self.ids.insert(key, self.sets.len());
+		self.sets.push(Rc::new(RefCell::new(set)));
+
+		return self.sets[self.sets.len()-1].clone();
+	}
+
+	/* Id of the interned set equal to set, None if there is none or the id doesn't fit a u16 */
+	pub fn id_of(&self, set: &SornSet) -> Option<u16>
+	{
+		let id = *self.ids.get(&(set.sets.clone(), set.contains_inf))?;
+
+		return u16::try_from(id).ok();
+	}
+
+	pub fn get(&self, id: u16) -> Option<Rc<RefCell<SornSet>>>
+	{
+		self.sets.get(id as usize).cloned()
+	}
+
+	pub fn len(&self) -> usize
+	{
+		self.sets.len()
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		self.sets.is_empty()
+	}
+}
+
 impl std::fmt::Debug for SornSet 
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
@@ -534,4 +1622,740 @@ impl std::fmt::Debug for SornSet
         }
         list.finish()
     }
+}
+/* Testing */
+#[cfg(test)]
+mod tests 
+{
+	use super::*;
+	use super::SornValue::*;
+
+	#[test]
+	fn test_try_from_str()
+	{
+		assert_eq!(SornValue::try_from("[1.5]"), Ok(Exact(1.5)));
+		assert_eq!(SornValue::try_from("(0,1)"), Ok(Open((0.0, 1.0))));
+		assert_eq!(SornValue::try_from("(0,1]"), Ok(OpenLeft((0.0, 1.0))));
+		assert_eq!(SornValue::try_from("[-1, 0)"), Ok(OpenRight((-1.0, 0.0))));
+		assert_eq!(SornValue::try_from("[±inf]"), Ok(PlusMinusInf));
+		assert_eq!(SornValue::try_from(" [2] "), Ok(Exact(2.0)));
+		assert_eq!(SornValue::try_from("(-inf,0)"), Ok(Open((Real::NEG_INFINITY, 0.0))));
+		assert_eq!(SornValue::try_from("(1,inf)"), Ok(Open((1.0, Real::INFINITY))));
+	}
+
+	#[test]
+	fn test_try_from_str_malformed()
+	{
+		assert_eq!(SornValue::try_from(""), Err(SornValueParseError::Empty));
+		assert_eq!(SornValue::try_from("1.5"), Err(SornValueParseError::MissingBrackets));
+		assert_eq!(SornValue::try_from("[1.5"), Err(SornValueParseError::MissingBrackets));
+		assert_eq!(SornValue::try_from("(a,1)"), Err(SornValueParseError::InvalidNumber));
+		assert_eq!(SornValue::try_from("[]"), Err(SornValueParseError::InvalidNumber));
+		assert_eq!(SornValue::try_from("(1)"), Err(SornValueParseError::InvalidFormat));
+		assert_eq!(SornValue::try_from("[0,1]"), Err(SornValueParseError::InvalidFormat));
+		assert_eq!(SornValue::try_from("(0,1,2)"), Err(SornValueParseError::InvalidFormat));
+		assert_eq!(SornValue::try_from("(2,1)"), Err(SornValueParseError::InvertedBounds));
+		assert_eq!(SornValue::try_from("[inf]"), Err(SornValueParseError::InvalidNumber));
+		assert_eq!(SornValue::try_from("[NaN]"), Err(SornValueParseError::InvalidNumber));
+		assert_eq!(SornValue::try_from("(NaN,1)"), Err(SornValueParseError::InvalidNumber));
+		assert_eq!(SornValue::try_from("[-inf,0)"), Err(SornValueParseError::InvalidNumber));
+		assert_eq!(SornValue::try_from("(inf,inf)"), Err(SornValueParseError::InvalidNumber));
+	}
+
+	#[test]
+	fn test_point_counts()
+	{
+		/* [0], (0,1), [1], (1,2), [2], (2,3), [3], (3,4), [4] */
+		let set = SornSet::new(0.0, 4.0, 1.0, false);
+
+		assert_eq!(set.distinct_count(), 9);
+		assert_eq!(set.real_point_count(), 5);
+		assert_eq!(set.distinct_count() - set.real_point_count(), 4);
+	}
+
+	#[test]
+	fn test_try_push_limit()
+	{
+		let mut set = SornSet::default();
+
+		for i in 0..sorn_max_bits()
+		{
+			assert!(set.try_push(Exact(i as Real)).is_ok());
+		}
+
+		assert!(matches!(set.try_push(Exact(-1.0)), Err(SornErrors::SetTooLarge)));
+		assert_eq!(set.len(), sorn_max_bits());
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_push_past_limit()
+	{
+		/* 128 steps need 257 elements */
+		let _ = SornSet::new(0.0, 64.0, 0.5, false);
+	}
+
+	#[test]
+	fn test_cache_memory()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		assert_eq!(set.borrow().cache_len(), 0);
+		assert_eq!(set.borrow().cache_memory_bytes(), 0);
+
+		let mut a = Sorn::new(set.clone());
+		let mut b = Sorn::new(set.clone());
+		a.set_bits(0b00010).unwrap();
+		b.set_bits(0b01000).unwrap();
+		let _ = &a + &b;
+
+		/* Addition stores both operand orders */
+		assert_eq!(set.borrow().cache_len(), 2);
+		let populated = set.borrow().cache_memory_bytes();
+		assert!(populated > 0);
+
+		let _ = &a * &a;
+		assert!(set.borrow().cache_memory_bytes() > populated);
+
+		set.borrow_mut().clear_caches();
+		assert_eq!(set.borrow().cache_len(), 0);
+	}
+
+	#[test]
+	fn test_cache_capacity()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		set.borrow_mut().set_cache_capacity(2);
+
+		let sorns: Vec<Sorn> = (0..3).map(|i|
+		{
+			let mut sorn = Sorn::new(set.clone());
+			sorn.set_bits(1 << i).unwrap();
+			sorn
+		}).collect();
+
+		let _ = &sorns[0] - &sorns[0];
+		let _ = &sorns[1] - &sorns[1];
+		assert_eq!(set.borrow().precomputed_sub.len(), 2);
+
+		/* Touch the first entry again so the second one is the oldest */
+		let _ = &sorns[0] - &sorns[0];
+		let _ = &sorns[2] - &sorns[2];
+
+		let cache = &set.borrow().precomputed_sub;
+		assert_eq!(cache.len(), 2);
+		assert!(cache.contains_key(&(0b001, 0b001)));
+		assert!(!cache.contains_key(&(0b010, 0b010)));
+		assert!(cache.contains_key(&(0b100, 0b100)));
+	}
+
+	#[test]
+	fn test_cache_unbounded_by_default()
+	{
+		let mut cache: OpCache<SornBitsType> = OpCache::default();
+
+		for i in 0..100
+		{
+			cache.insert(i, i);
+		}
+
+		assert_eq!(cache.len(), 100);
+		assert_eq!(cache.get(&42), Some(42));
+	}
+
+	#[test]
+	fn test_cache_eviction_order()
+	{
+		let mut cache: OpCache<SornBitsType> = OpCache::default();
+
+		for i in 0..5
+		{
+			cache.insert(i, i);
+		}
+
+		/* Reinserting and reading both count as a use */
+		cache.insert(0, 10);
+		assert_eq!(cache.get(&1), Some(1));
+
+		cache.set_capacity(Some(2));
+		assert_eq!(cache.len(), 2);
+		assert_eq!(cache.get(&0), Some(10));
+		assert_eq!(cache.get(&1), Some(1));
+
+		cache.insert(5, 5);
+		assert!(!cache.contains_key(&0));
+		assert_eq!(cache.recency.len(), cache.len());
+	}
+
+	#[test]
+	fn test_grid_style()
+	{
+		let default = SornSet::new_with_style(0.0, 2.0, 1.0, false, GridStyle::default());
+		assert_eq!(default, SornSet::new(0.0, 2.0, 1.0, false));
+
+		let style = GridStyle { gaps: GapKind::OpenRight, exact_points: false };
+		let set = SornSet::new_with_style(0.0, 2.0, 1.0, false, style);
+		assert_eq!(set.sets, vec![OpenRight((0.0, 1.0)), OpenRight((1.0, 2.0)), Exact(2.0)]);
+		/* 1 is the closed left end of the second gap */
+		assert_eq!(set.one_bit, 0b10);
+		assert_ne!(Sorn::sorn_to_bits(Rc::new(RefCell::new(set.clone())), &Exact(2.0)), 0);
+
+		let style = GridStyle { gaps: GapKind::OpenLeft, exact_points: false };
+		let set = SornSet::new_with_style(0.0, 2.0, 1.0, false, style);
+		assert_eq!(set.sets, vec![Exact(0.0), OpenLeft((0.0, 1.0)), OpenLeft((1.0, 2.0))]);
+		assert_eq!(set.zero_bit, 0b1);
+
+		/* Half open gaps next to exact points would overlap them */
+		let style = GridStyle { gaps: GapKind::OpenLeft, exact_points: true };
+		let set = SornSet::new_with_style(0.0, 1.0, 1.0, true, style);
+		assert_eq!(set.sets, vec![PlusMinusInf, Open((Real::NEG_INFINITY, 0.0)), Exact(0.0), Open((0.0, 1.0)), Exact(1.0), Open((1.0, Real::INFINITY))]);
+		assert_eq!(set.one_bit.count_ones(), 1);
+	}
+
+	#[test]
+	fn test_dedup()
+	{
+		let mut set = SornSet::from_string("[0];(0,1);[1];(0,1);[0]");
+		assert_eq!(set.len(), 5);
+
+		set.dedup();
+		assert_eq!(set.len(), 3);
+		assert_eq!(set.sets, vec![Exact(0.0), Open((0.0, 1.0)), Exact(1.0)]);
+		assert_eq!(set.one_bit, 0b100);
+	}
+
+	#[test]
+	fn test_try_new()
+	{
+		assert!(SornSet::try_new(0.0, 1.0, 0.5, false).is_ok());
+
+		assert!(matches!(SornSet::try_new(0.0, 1.0, 0.0, false), Err(SornErrors::InvalidRange)));
+		assert!(matches!(SornSet::try_new(0.0, 1.0, -0.5, false), Err(SornErrors::InvalidRange)));
+		assert!(matches!(SornSet::try_new(1.0, 1.0, 0.5, false), Err(SornErrors::InvalidRange)));
+		assert!(matches!(SornSet::try_new(1.0, 0.0, 0.5, false), Err(SornErrors::InvalidRange)));
+		assert!(matches!(SornSet::try_new(0.0, Real::NAN, 0.5, true), Err(SornErrors::InvalidRange)));
+
+		/* 128 gaps and 129 exact points, or 64 gaps with [±inf] and the tails */
+		assert!(matches!(SornSet::try_new(0.0, 64.0, 0.5, false), Err(SornErrors::SetTooLarge)));
+		assert!(matches!(SornSet::try_new(0.0, 32.0, 0.5, true), Err(SornErrors::SetTooLarge)));
+		assert!(matches!(SornSet::try_new(0.0, Real::INFINITY, 1.0, false), Err(SornErrors::SetTooLarge)));
+		assert_eq!(SornSet::try_new(0.0, 31.0, 0.5, true).unwrap().len(), 128);
+		assert_eq!(SornSet::try_new(0.0, 63.0, 1.0, false).unwrap().len(), 127);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_new_inverted()
+	{
+		let _ = SornSet::new(1.0, -1.0, 0.5, false);
+	}
+
+	#[test]
+	fn test_set_contains_inf()
+	{
+		let mut set = SornSet::new(-1.0, 1.0, 1.0, false);
+
+		set.set_contains_inf(true);
+		assert!(set.contains_inf());
+		assert_eq!(set, SornSet::new(-1.0, 1.0, 1.0, true));
+		assert_eq!(set.one_bit, 1 << 6);
+
+		set.set_contains_inf(false);
+		assert!(!set.contains_inf());
+		assert_eq!(set, SornSet::new(-1.0, 1.0, 1.0, false));
+		assert_eq!(set.one_bit, 1 << 4);
+	}
+
+	#[test]
+	fn test_set_contains_inf_clears_caches()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let a = Sorn::from_sornvalue(set.clone(), Exact(1.0));
+		let _ = &a + &a;
+		assert!(set.borrow().cache_len() > 0);
+
+		set.borrow_mut().set_contains_inf(true);
+		assert_eq!(set.borrow().cache_len(), 0);
+	}
+
+	#[test]
+	fn test_quantize()
+	{
+		let set = SornSet::new(0.0, 2.0, 1.0, true);
+
+		assert_eq!(set.quantize(0.9), Some(1.0));
+		assert_eq!(set.quantize(1.2), Some(1.0));
+		assert_eq!(set.quantize(0.5), Some(0.0));
+		assert_eq!(set.quantize(1.5), Some(1.0));
+		assert_eq!(set.quantize(-10.0), Some(0.0));
+		assert_eq!(set.quantize(10.0), Some(2.0));
+
+		let mut gaps_only = SornSet::default();
+		gaps_only.push(Open((0.0, 1.0)));
+		gaps_only.push(Open((1.0, 2.0)));
+		assert_eq!(gaps_only.quantize(0.5), None);
+	}
+
+	#[test]
+	fn test_contains_point()
+	{
+		assert!(Exact(1.0).contains_point(1.0));
+		assert!(!Exact(1.0).contains_point(1.5));
+
+		assert!(!Open((0.0, 1.0)).contains_point(0.0));
+		assert!(Open((0.0, 1.0)).contains_point(0.5));
+		assert!(!Open((0.0, 1.0)).contains_point(1.0));
+
+		assert!(!OpenLeft((0.0, 1.0)).contains_point(0.0));
+		assert!(OpenLeft((0.0, 1.0)).contains_point(1.0));
+
+		assert!(OpenRight((0.0, 1.0)).contains_point(0.0));
+		assert!(!OpenRight((0.0, 1.0)).contains_point(1.0));
+
+		assert!(PlusMinusInf.contains_point(Real::INFINITY));
+		assert!(!PlusMinusInf.contains_point(0.0));
+		assert!(!Empty.contains_point(0.0));
+		assert!(!Open((0.0, 1.0)).contains_point(Real::NAN));
+	}
+
+	#[test]
+	fn test_overlaps()
+	{
+		assert!(Open((0.0, 1.0)).overlaps(&Open((0.5, 2.0))));
+		assert!(!Open((0.0, 1.0)).overlaps(&Open((1.0, 2.0))));
+		assert!(!Open((0.0, 1.0)).overlaps(&Exact(1.0)));
+		assert!(OpenLeft((0.0, 1.0)).overlaps(&Exact(1.0)));
+		assert!(OpenLeft((0.0, 1.0)).overlaps(&OpenRight((1.0, 2.0))));
+		assert!(!OpenRight((0.0, 1.0)).overlaps(&OpenRight((1.0, 2.0))));
+		assert!(PlusMinusInf.overlaps(&PlusMinusInf));
+		assert!(!PlusMinusInf.overlaps(&Open((1.0, Real::INFINITY))));
+		assert!(!Open((0.0, 1.0)).overlaps(&Exact(Real::NAN)));
+	}
+
+	#[test]
+	fn test_same_finite_structure()
+	{
+		let finite = SornSet::new(-1.0, 1.0, 1.0, false);
+		let with_inf = SornSet::new(-1.0, 1.0, 1.0, true);
+
+		assert_ne!(finite, with_inf);
+		assert!(finite.same_finite_structure(&with_inf));
+		assert!(with_inf.same_finite_structure(&finite));
+
+		assert!(!finite.same_finite_structure(&SornSet::new(-1.0, 2.0, 1.0, true)));
+	}
+
+	#[test]
+	fn test_normalize()
+	{
+		let mut set = SornSet::default();
+		set.push(Exact(1.0));
+		set.push(Open((0.0, 1.0)));
+		set.push(Exact(0.0));
+		set.push(Exact(1.0));
+		set.push(PlusMinusInf);
+
+		set.normalize();
+		assert_eq!(set.sets, vec![PlusMinusInf, Exact(0.0), Open((0.0, 1.0)), Exact(1.0)]);
+		assert_eq!(set.one_bit, 1 << 3);
+	}
+
+	#[test]
+	fn test_normalize_eps()
+	{
+		/* 1e-12 is below the resolution of f32 */
+		let d: Real = if cfg!(feature = "f32") { 1e-6 } else { 1e-12 };
+
+		let mut set = SornSet::default();
+		set.push(Exact(0.3));
+		set.push(Open((0.0, 0.3)));
+		set.push(Exact(0.3 + d));
+		set.push(Open((0.0, 0.3 - d)));
+
+		let mut exact = set.clone();
+		exact.normalize();
+		assert_eq!(exact.len(), 4);
+
+		set.normalize_eps(d * 1000.0);
+		assert_eq!(set.sets, vec![Open((0.0, 0.3 - d)), Exact(0.3)]);
+	}
+
+	#[test]
+	fn test_is_closed_under()
+	{
+		let with_inf = SornSet::new(-1.0, 1.0, 1.0, true);
+		assert!(with_inf.is_closed_under(Op::Add));
+		assert!(with_inf.is_closed_under(Op::Mul));
+
+		/* 1 + 1 is out of range */
+		let finite = SornSet::new(0.0, 1.0, 1.0, false);
+		assert!(!finite.is_closed_under(Op::Add));
+		assert!(finite.is_closed_under(Op::Mul));
+	}
+
+	#[test]
+	fn test_attach_table()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let mut a = Sorn::new(set.clone());
+		let mut b = Sorn::new(set.clone());
+		a.set_bits(0b00110).unwrap();
+		b.set_bits(0b11000).unwrap();
+		let expected = &a + &b;
+
+		let add = gen_table(set.clone(), "add");
+		let mul = gen_table(set.clone(), "mul");
+		set.borrow_mut().clear_caches();
+
+		set.borrow_mut().attach_table(Op::Add, &add).unwrap();
+		assert_eq!(add.lookup(a.bits, b.bits), expected.bits);
+		assert_eq!(&a + &b, expected);
+
+		/* The cross product would have filled the cache */
+		assert_eq!(set.borrow().precomputed_add.len(), 0);
+
+		/* Whatever table is attached is used, even a wrong one */
+		set.borrow_mut().attach_table(Op::Add, &mul).unwrap();
+		assert_eq!((&a + &b).bits, mul.lookup(a.bits, b.bits));
+
+		let other = gen_table(Rc::new(RefCell::new(SornSet::new(0.0, 1.0, 1.0, false))), "add");
+		assert!(matches!(set.borrow_mut().attach_table(Op::Add, &other), Err(SornErrors::DimensionMismatch)));
+	}
+
+	#[cfg(feature = "metrics")]
+	#[test]
+	fn test_table_hit_metrics()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let mut a = Sorn::new(set.clone());
+		let mut b = Sorn::new(set.clone());
+		a.set_bits(0b00110).unwrap();
+		b.set_bits(0b11000).unwrap();
+
+		let add = gen_table(set.clone(), "add");
+		set.borrow_mut().clear_caches();
+		set.borrow_mut().attach_table(Op::Add, &add).unwrap();
+		let before = set.borrow().metrics().add;
+
+		let _ = &a + &b;
+
+		/* The table answered, so the cache was neither hit nor missed */
+		let after = set.borrow().metrics().add;
+		assert_eq!(after.table_hits, before.table_hits + 1);
+		assert_eq!(after.misses, before.misses);
+		assert_eq!(after.hits, before.hits);
+	}
+
+	#[test]
+	fn test_iter_mut_recompute()
+	{
+		/* [0], (0,1), [1], (1,2), [2] */
+		let mut set = SornSet::new(0.0, 2.0, 1.0, false);
+		assert_eq!(set.one_bit, 1 << 2);
+
+		for item in set.iter_mut()
+		{
+			*item = match *item
+			{
+				Exact(v) => Exact(v * 0.5),
+				Open((start, end)) => Open((start * 0.5, end * 0.5)),
+				other => other,
+			};
+		}
+		set.recompute();
+
+		assert_eq!(set.sets, vec![Exact(0.0), Open((0.0, 0.5)), Exact(0.5), Open((0.5, 1.0)), Exact(1.0)]);
+		assert_eq!(set.one_bit, 1 << 4);
+	}
+
+	#[test]
+	fn test_affine()
+	{
+		let set = SornSet::new(-1.0, 1.0, 1.0, false);
+		let moved = set.affine(5.0, 5.0);
+
+		assert_eq!(moved.sets, vec![Exact(0.0), Open((0.0, 5.0)), Exact(5.0), Open((5.0, 10.0)), Exact(10.0)]);
+		assert_eq!(moved, SornSet::new(0.0, 10.0, 5.0, false));
+		assert_eq!(moved.one_bit, 1 << 1);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_affine_zero_scale()
+	{
+		let _ = SornSet::new(0.0, 1.0, 1.0, true).affine(0.0, 1.0);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_affine_infinite_scale()
+	{
+		let _ = SornSet::new(0.0, 1.0, 1.0, false).affine(Real::INFINITY, 0.0);
+	}
+
+	#[test]
+	fn test_affine_negative_scale()
+	{
+		let set = SornSet::new_with_style(0.0, 2.0, 1.0, true, GridStyle { gaps: GapKind::OpenLeft, exact_points: false });
+		let flipped = set.affine(-1.0, 0.0);
+
+		assert_eq!(flipped.sets, vec![
+			PlusMinusInf,
+			Open((Real::NEG_INFINITY, -2.0)),
+			OpenRight((-2.0, -1.0)),
+			OpenRight((-1.0, 0.0)),
+			Exact(0.0),
+			Open((0.0, Real::INFINITY)),
+		]);
+	}
+
+	#[test]
+	fn test_from_breakpoints()
+	{
+		let set = SornSet::from_breakpoints(&[-1.0, 0.5, 4.0], true);
+
+		assert_eq!(set.sets, vec![
+			PlusMinusInf,
+			Open((Real::NEG_INFINITY, -1.0)),
+			Exact(-1.0),
+			Open((-1.0, 0.5)),
+			Exact(0.5),
+			Open((0.5, 4.0)),
+			Exact(4.0),
+			Open((4.0, Real::INFINITY)),
+		]);
+		assert_eq!(set.one_bit, 1 << 5);
+
+		assert_eq!(SornSet::from_breakpoints(&[0.0, 1.0, 2.0], false), SornSet::new(0.0, 2.0, 1.0, false));
+
+		assert!(matches!(SornSet::try_from_breakpoints(&[], false), Err(SornErrors::InvalidRange)));
+		assert!(matches!(SornSet::try_from_breakpoints(&[0.0, 2.0, 1.0], false), Err(SornErrors::InvalidRange)));
+		assert!(matches!(SornSet::try_from_breakpoints(&[0.0, 1.0, 1.0], false), Err(SornErrors::InvalidRange)));
+
+		/* 64 breakpoints need 127 elements, 130 with infinity */
+		let points: Vec<Real> = (0..100).map(|i| i as Real).collect();
+		assert!(matches!(SornSet::try_from_breakpoints(&points, false), Err(SornErrors::SetTooLarge)));
+		assert_eq!(SornSet::try_from_breakpoints(&points[..64], false).unwrap().len(), 127);
+		assert!(matches!(SornSet::try_from_breakpoints(&points[..64], true), Err(SornErrors::SetTooLarge)));
+		assert_eq!(SornSet::try_from_breakpoints(&points[..63], true).unwrap().len(), sorn_max_bits());
+	}
+
+	#[test]
+	fn test_registry_intern()
+	{
+		let mut registry = SornSetRegistry::new();
+
+		let first = registry.intern(SornSet::new(0.0, 2.0, 1.0, true));
+		let second = registry.intern(SornSet::new(0.0, 2.0, 1.0, true));
+		let other = registry.intern(SornSet::new(0.0, 2.0, 1.0, false));
+
+		assert!(Rc::ptr_eq(&first, &second));
+		assert!(!Rc::ptr_eq(&first, &other));
+		assert_eq!(registry.len(), 2);
+
+		/* The shared instance also shares the caches */
+		let a = Sorn::from_f64(first.clone(), 0.5);
+		let b = Sorn::from_f64(second.clone(), 1.5);
+		let _ = &a + &b;
+		assert!(!second.borrow().precomputed_add.is_empty());
+
+		/* -0.0 and 0.0 compare equal, so they intern as one set */
+		let negative_zero = SornSet::from_breakpoints(&[-0.0, 1.0], false);
+		let zero = SornSet::from_breakpoints(&[0.0, 1.0], false);
+		let hash = |set: &SornSet|
+		{
+			let mut hasher = std::hash::DefaultHasher::new();
+			std::hash::Hash::hash(set, &mut hasher);
+			std::hash::Hasher::finish(&hasher)
+		};
+		assert_eq!(negative_zero, zero);
+		assert_eq!(hash(&negative_zero), hash(&zero));
+
+		let mut registry = SornSetRegistry::new();
+		let first = registry.intern(negative_zero);
+		assert!(Rc::ptr_eq(&first, &registry.intern(zero.clone())));
+		assert_eq!(registry.len(), 1);
+		assert_eq!(registry.id_of(&zero), Some(0));
+	}
+
+	#[test]
+	fn test_inf_layout()
+	{
+		let set = SornSet::new(0.0, 1.0, 1.0, true);
+
+		assert_eq!(set.sets, vec![PlusMinusInf, Open((Real::NEG_INFINITY, 0.0)), Exact(0.0), Open((0.0, 1.0)), Exact(1.0), Open((1.0, Real::INFINITY))]);
+		assert_eq!(set.inf_bits(), 0b100011);
+		assert_eq!(SornSet::new(0.0, 1.0, 1.0, false).inf_bits(), 0);
+
+		/* The infinities land in [±inf], large finite values in the tails */
+		let set = Rc::new(RefCell::new(set));
+		assert_eq!(Sorn::from_f64(set.clone(), Real::INFINITY).bits, 0b1);
+		assert_eq!(Sorn::from_f64(set.clone(), Real::NEG_INFINITY).bits, 0b1);
+		assert_eq!(Sorn::from_f64(set.clone(), 1e30).bits, 0b100000);
+		assert_eq!(Sorn::from_f64(set.clone(), -1e30).bits, 0b10);
+
+		let inf = Sorn::from_sornvalue(set.clone(), PlusMinusInf);
+		assert!(inf.contains(PlusMinusInf));
+		assert!(inf.is_unbounded());
+		assert!(Sorn::from_f64(set.clone(), 1e30).is_unbounded());
+		assert!(!Sorn::from_f64(set.clone(), 0.5).is_unbounded());
+		assert!(!Sorn::from_f64(set.clone(), 0.5).contains(PlusMinusInf));
+	}
+
+	#[test]
+	fn test_from_string_sorted()
+	{
+		let set = SornSet::from_string_sorted("(1,2);[0];[2];(0,1);[1]");
+
+		assert_eq!(set, SornSet::new(0.0, 2.0, 1.0, false));
+		assert_eq!(set.one_bit, 1 << 2);
+
+		let set = Rc::new(RefCell::new(set));
+		let sum = Sorn::from_f64(set.clone(), 0.5) + Sorn::from_f64(set.clone(), 1.0);
+		assert_eq!(sum.get_ranges().sets, vec![Open((1.0, 2.0))]);
+	}
+
+	#[test]
+	fn test_display_precision()
+	{
+		assert_eq!(format!("{:.3}", Open((0.0, 1.0 / 3.0))), "(0.000,0.333)");
+		assert_eq!(format!("{:.3}", OpenLeft((-0.5, 2.0))), "(-0.500,2.000]");
+		assert_eq!(format!("{:.3}", OpenRight((1.0, 1.5))), "[1.000,1.500)");
+		assert_eq!(format!("{:.3}", Exact(2.0 / 3.0)), "[0.667]");
+		assert_eq!(format!("{:.3}", PlusMinusInf), "[±inf]");
+		assert_eq!(format!("{:.3}", Empty), "Empty SornValue");
+
+		/* Without flags it is the Debug notation */
+		assert_eq!(format!("{}", Open((0.0, 0.5))), format!("{:?}", Open((0.0, 0.5))));
+
+		assert_eq!(format!("{:>8.1}", Exact(1.0)), "   [1.0]");
+		assert_eq!(format!("{:8}|", Exact(1.0)), "[1]     |");
+		assert_eq!(format!("{:*^7}", Exact(1.0)), "**[1]**");
+	}
+
+	#[test]
+	fn test_all_sorns()
+	{
+		let set = Rc::new(RefCell::new(SornSet::from_string("[-1];(-1,0);[0];(0,1)")));
+		let all: Vec<Sorn> = SornSet::all_sorns(set.clone()).unwrap().collect();
+
+		assert_eq!(all.len(), 16);
+		assert_eq!(all.iter().map(|sorn| sorn.bits).collect::<FxHashSet<_>>().len(), 16);
+
+		for a in &all
+		{
+			for b in &all
+			{
+				assert_eq!(a + b, b + a);
+			}
+		}
+
+		let large = Rc::new(RefCell::new(SornSet::new(0.0, 20.0, 1.0, false)));
+		assert!(matches!(SornSet::all_sorns(large), Err(SornErrors::SetTooLarge)));
+	}
+
+	#[test]
+	fn test_check_algebra()
+	{
+		/* [-1], (-1,0), [0], (0,1), [1] */
+		let set = SornSet::new(-1.0, 1.0, 1.0, false);
+
+		assert!(set.check_commutative(Op::Add).unwrap());
+		assert!(!set.check_commutative(Op::Sub).unwrap());
+
+		/* ([1] + [1]) + [-1] leaves the set in the first step, [1] + ([1] + [-1]) is [1] */
+		let broken = set.check_associative(Op::Add).unwrap();
+		assert!(broken.contains(&(0b10000, 0b10000, 0b00001)));
+		assert!(!broken.contains(&(0b00100, 0b00100, 0b00100)));
+
+		/* 11 and 7 elements, each one past the limit of its check */
+		let large = SornSet::new(0.0, 5.0, 1.0, false);
+		assert!(matches!(large.check_commutative(Op::Add), Err(SornErrors::SetTooLarge)));
+		let medium = SornSet::new(0.0, 3.0, 1.0, false);
+		assert!(matches!(medium.check_associative(Op::Add), Err(SornErrors::SetTooLarge)));
+		assert!(medium.check_commutative(Op::Mul).unwrap());
+	}
+
+	#[test]
+	fn test_floor_ceil_exact()
+	{
+		let set = SornSet::new(0.0, 2.0, 0.5, true);
+
+		assert_eq!(set.floor_exact(0.7), Some(0.5));
+		assert_eq!(set.ceil_exact(0.7), Some(1.0));
+		assert_eq!(set.floor_exact(1.5), Some(1.5));
+		assert_eq!(set.ceil_exact(1.5), Some(1.5));
+		assert_eq!(set.floor_exact(9.0), Some(2.0));
+
+		assert_eq!(set.floor_exact(-0.1), None);
+		assert_eq!(set.ceil_exact(2.1), None);
+		assert_eq!(set.floor_exact(Real::NAN), None);
+	}
+
+	#[test]
+	fn test_new_symmetric()
+	{
+		let set = SornSet::new_symmetric(1.0, 0.5, false);
+		assert_eq!(set, SornSet::new(-1.0, 1.0, 0.5, false));
+
+		/* 0.3 doesn't divide 1, ±1 are still the outermost points */
+		let set = SornSet::new_symmetric(1.0, 0.3, true);
+		assert!(set.sets.contains(&Exact(0.0)));
+		assert_eq!(set.get(2), Exact(-1.0));
+		assert_eq!(set.affine(-1.0, 0.0), set);
+
+		let set = Rc::new(RefCell::new(set));
+		for i in 0..set.borrow().len()
+		{
+			let mut sorn = Sorn::new(set.clone());
+			sorn.set_bits(1 << i).unwrap();
+
+			let mut negated = sorn.negate();
+			assert_eq!(negated.cardinality(), 1);
+			assert_eq!(negated.negate(), sorn);
+		}
+
+		assert!(matches!(SornSet::try_new_symmetric(1.0, 1e-12, false), Err(SornErrors::SetTooLarge)));
+		assert!(matches!(SornSet::try_new_symmetric(100.0, 1.0, false), Err(SornErrors::SetTooLarge)));
+		assert!(matches!(SornSet::try_new_symmetric(Real::INFINITY, 1.0, false), Err(SornErrors::InvalidRange)));
+		assert!(matches!(SornSet::try_new_symmetric(1.0, 0.0, false), Err(SornErrors::InvalidRange)));
+
+		/* 31 points per side and [0] need 125 elements, 128 with infinity */
+		assert_eq!(SornSet::try_new_symmetric(31.0, 1.0, true).unwrap().len(), sorn_max_bits());
+		assert!(matches!(SornSet::try_new_symmetric(32.0, 1.0, false), Err(SornErrors::SetTooLarge)));
+	}
+
+	#[test]
+	fn test_refined_like()
+	{
+		let set = SornSet::new(-1.0, 2.0, 1.0, true);
+		let refined = set.refined_like(4);
+
+		for item in set.sets.iter().filter(|item| item.is_exact())
+		{
+			assert!(refined.sets.contains(item));
+		}
+
+		assert_eq!(refined.contains_inf(), set.contains_inf());
+		assert_eq!(refined.first(), set.first());
+		assert_eq!(refined.real_point_count(), 13);
+		assert!(refined.sets.contains(&SornValue::Open((0.25, 0.5))));
+
+		/* Uneven spacing and half open buckets, their endpoints become exact points */
+		let uneven = SornSet::from_string("[0];(0,0.5];(0.5,3)");
+		let refined = uneven.refined_like(2);
+		assert!(!refined.contains_inf());
+		assert!(refined.sets.contains(&SornValue::Exact(0.0)));
+		assert!(refined.sets.contains(&SornValue::Exact(0.5)));
+		assert!(refined.sets.contains(&SornValue::Exact(1.75)));
+		assert_eq!(refined.sets.last(), Some(&SornValue::Exact(3.0)));
+
+		assert_eq!(set.refined_like(1), set);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_refined_like_too_large()
+	{
+		let _ = SornSet::new(0.0, 2.0, 1.0, false).refined_like(usize::MAX);
+	}
 }
\ No newline at end of file
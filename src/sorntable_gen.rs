@@ -1,11 +1,12 @@
 use std::{cell::RefCell, rc::Rc};
 
-use crate::{sorn::Sorn, sornset::SornSet, sorn::SornBitsType};
+use crate::{sorn::Sorn, sornset::SornSet, sorn::SornBitsType, sorn::SornErrors, sorn::sorn_max_bits};
 pub struct SornTable
 {
 	sorn_sets: Rc<RefCell<SornSet>>,
 
 	header: Vec<SornBitsType>,
+	col_header: Vec<SornBitsType>,
 	table_data: Vec<Vec<SornBitsType>>
 }
 
@@ -20,7 +21,7 @@ pub fn gen_table(sorn_sets: Rc<RefCell<SornSet>>, operator: &str) -> SornTable
 	let mut table_data = vec![vec![0; num_sets]; num_sets];
 
 	/* Create a SORN for every bit */
-    for i in 0..sorn_sets.borrow().len()
+    for i in 0..num_sets
     {
         let mut sorn = Sorn::new(sorn_sets.clone());
 
@@ -31,26 +32,25 @@ pub fn gen_table(sorn_sets: Rc<RefCell<SornSet>>, operator: &str) -> SornTable
     }
 
 	/* Write every SORN generated before in the header as bits */
-    for i in 0..sorn_sets.borrow().len()
+    for i in 0..num_sets
     {
         header[i] = sorns[i].bits;
     }
 
 	/* Write the Tabledata */
-    for i in 0..sorn_sets.borrow().len()
+    for i in 0..num_sets
     {
-        for j in 0..sorn_sets.borrow().len()
+        for j in 0..num_sets
         {
-			let mut cur = Sorn::default();
-			match operator
+			let cur = match operator
 			{
-				"add" => cur = sorns[i].clone() + sorns[j].clone(),
-				"sub" => cur = sorns[i].clone() - sorns[j].clone(),
-				"mul" => cur = sorns[i].clone() * sorns[j].clone(),
-				"div" => cur = sorns[i].clone() / sorns[j].clone(),
+				"add" => sorns[i].clone() + sorns[j].clone(),
+				"sub" => sorns[i].clone() - sorns[j].clone(),
+				"mul" => sorns[i].clone() * sorns[j].clone(),
+				"div" => sorns[i].clone() / sorns[j].clone(),
 
 				_ => panic!("Tried to generate SORN Table without valid operator, use 'add', 'sub', 'mul' or 'div'")
-			}
+			};
 
             table_data[j][i] = cur.bits; 
         }
@@ -59,33 +59,169 @@ pub fn gen_table(sorn_sets: Rc<RefCell<SornSet>>, operator: &str) -> SornTable
     SornTable
 	{
 		sorn_sets: sorn_sets.clone(),
+		col_header: header.clone(),
 		header,
 		table_data,
 	}
 }
 
+/*
+Single column table of x.pow(power) for every single element x. There is no second
+operand, so the header of the column is 0.
+*/
+pub fn gen_pow_table(sorn_sets: Rc<RefCell<SornSet>>, power: i32) -> SornTable
+{
+	let num_sets = sorn_sets.borrow().len();
+
+	let mut header = vec![0; num_sets];
+	let mut table_data = vec![vec![0; 1]; num_sets];
+
+	for i in 0..num_sets
+	{
+		let mut sorn = Sorn::new(sorn_sets.clone());
+
+		/* Will always be valid in this case, so we dont need to check the return value */
+		let _ = sorn.set_bits(1 << i);
+
+		header[i] = sorn.bits;
+		table_data[i][0] = sorn.pow(power).bits;
+	}
+
+	SornTable
+	{
+		sorn_sets: sorn_sets.clone(),
+		header,
+		col_header: vec![0],
+		table_data,
+	}
+}
+
+/* ORs data[j][i] for every bit i of lhs and j of rhs, the layout gen_table writes */
+pub(crate) fn lookup_cells(data: &[Vec<SornBitsType>], lhs: SornBitsType, rhs: SornBitsType) -> SornBitsType
+{
+	let mut result = 0;
+
+	for row in data.iter().enumerate().filter(|(j, _)| rhs & (1 << j) != 0).map(|(_, row)| row)
+	{
+		for (i, cell) in row.iter().enumerate()
+		{
+			if lhs & (1 << i) != 0
+			{
+				result |= cell;
+			}
+		}
+	}
+
+	return result;
+}
+
+/* Mirrors the lowest len bits, so bit 0 becomes bit len-1 and the other way around */
+pub fn reverse_within_word(bits: SornBitsType, len: usize) -> SornBitsType
+{
+	if len == 0
+	{
+		return 0;
+	}
+
+	bits.reverse_bits() >> (sorn_max_bits() - len)
+}
+
 impl SornTable
 {
+	pub fn rows(&self) -> usize
+	{
+		self.table_data.len()
+	}
+
+	pub fn columns(&self) -> usize
+	{
+		self.col_header.len()
+	}
+
+	pub(crate) fn data(&self) -> &[Vec<SornBitsType>]
+	{
+		&self.table_data
+	}
+
+	/* Result of lhs op rhs for Sorns with any number of active bits */
+	pub fn lookup(&self, lhs: SornBitsType, rhs: SornBitsType) -> SornBitsType
+	{
+		lookup_cells(&self.table_data, lhs, rhs)
+	}
+
+	/* Every (row, column, own bits, other bits) where both tables disagree */
+	pub fn diff(&self, other: &SornTable) -> Result<Vec<(usize, usize, SornBitsType, SornBitsType)>, SornErrors>
+	{
+		if self.sorn_sets != other.sorn_sets
+		{
+			return Err(SornErrors::DifferentSornSets);
+		}
+
+		if self.rows() != other.rows() || self.columns() != other.columns()
+		{
+			return Err(SornErrors::DimensionMismatch);
+		}
+
+		let mut result = Vec::new();
+
+		for (i, (row, other_row)) in self.table_data.iter().zip(&other.table_data).enumerate()
+		{
+			for (j, (cell, other_cell)) in row.iter().zip(other_row).enumerate()
+			{
+				if cell != other_cell
+				{
+					result.push((i, j, *cell, *other_cell));
+				}
+			}
+		}
+
+		return Ok(result);
+	}
+
+	/* Every cell as (row header, column header, result bits), row by row */
+	pub fn iter_cells(&self) -> impl Iterator<Item = (SornBitsType, SornBitsType, SornBitsType)> + '_
+	{
+		self.table_data.iter().enumerate().flat_map(move |(i, row)|
+		{
+			row.iter().enumerate().map(move |(j, result)| (self.header[i], self.col_header[j], *result))
+		})
+	}
+
+	pub fn iter_nonzero_cells(&self) -> impl Iterator<Item = (SornBitsType, SornBitsType, SornBitsType)> + '_
+	{
+		self.iter_cells().filter(|(_, _, result)| *result != 0)
+	}
+
 	pub fn to_csv(&self) -> String
+	{
+		self.to_csv_with(false)
+	}
+
+	/* With reverse_bits the first set element is written as the leftmost bit instead of the rightmost */
+	pub fn to_csv_with(&self, reverse_bits: bool) -> String
 	{
 		let mut result: String = "".to_owned();
+		let len = self.sorn_sets.borrow().len();
+
+		/* Reversed words are zero padded to the set length, else leading zeros of high elements get lost */
+		let order = |bits: SornBitsType| if reverse_bits { format!("{:0len$b}", reverse_within_word(bits, len)) } else { format!("{:b}", bits) };
 
 		/* Add the Row Header */
 		result.push(',');
-		for item in &self.header
+		for item in &self.col_header
 		{
-			result.push_str(&format!("{:b},", item));
+			result.push_str(&format!("{},", order(*item)));
 		}
 		result.push('\n');
 
 		/* Add the Column Header alongside the Tabledata */
 		for (i, row) in self.table_data.iter().enumerate()
 		{
-			result.push_str(&format!("{:b},", self.header[i]));
+			result.push_str(&format!("{},", order(self.header[i])));
 
 			for col in row
 			{
-				result.push_str(&format!("{:b},", col));
+				result.push_str(&format!("{},", order(*col)));
 			}
 
 			result.push('\n');
@@ -95,9 +231,9 @@ impl SornTable
 	}
 }
 
-impl std::string::ToString for SornTable
+impl std::fmt::Display for SornTable
 {
-	fn to_string(&self) -> String 
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
 	{
 		let mut result: String = "".to_owned();
 
@@ -106,12 +242,12 @@ impl std::string::ToString for SornTable
 
 		/* Add the Row Header */
 		result.push_str("\t|\t");
-		for item in &self.header
+		for item in &self.col_header
 		{
 			result.push_str(&format!("{:b}\t|\t", item));
 		}
 		result.push('\n');
-		result.push_str(&"-".repeat(self.header.len() * 20));
+		result.push_str(&"-".repeat(self.col_header.len() * 20));
 		result.push('\n');
 
 		/* Add the Column Header alongside the Tabledata */
@@ -127,6 +263,98 @@ impl std::string::ToString for SornTable
 			result.push('\n');
 		}
 
-		return result;
+		write!(f, "{}", result)
+	}
+}
+
+/* Testing */
+#[cfg(test)]
+mod tests 
+{
+	use super::*;
+	use crate::sorn::Real;
+	use crate::sornset::SornValue;
+
+	#[test]
+	fn test_reverse_within_word()
+	{
+		assert_eq!(reverse_within_word(0b00001, 5), 0b10000);
+		assert_eq!(reverse_within_word(0b00110, 5), 0b01100);
+		assert_eq!(reverse_within_word(0b1, 1), 0b1);
+		assert_eq!(reverse_within_word(1, sorn_max_bits()), 1 << (sorn_max_bits() - 1));
+	}
+
+	#[test]
+	fn test_csv_reversed()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let table = gen_table(set.clone(), "add");
+
+		let normal = table.to_csv();
+		let reversed = table.to_csv_with(true);
+
+		/* Header and the row of (-1,0) */
+		assert_eq!(normal.lines().next().unwrap(), ",1,10,100,1000,10000,");
+		assert_eq!(reversed.lines().next().unwrap(), ",10000,01000,00100,00010,00001,");
+		assert_eq!(normal.lines().nth(2).unwrap(), "10,0,11,10,1110,1000,");
+		assert_eq!(reversed.lines().nth(2).unwrap(), "01000,00000,11000,01000,01110,00010,");
+	}
+
+	#[test]
+	fn test_iter_cells()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let table = gen_table(set.clone(), "add");
+		let len = set.borrow().len();
+
+		assert_eq!(table.iter_cells().count(), len * len);
+
+		/* Row (-1,0), column [1] is (0,1) */
+		assert!(table.iter_cells().any(|cell| cell == (0b10, 0b10000, 0b1000)));
+
+		/* [-1] + [-1] and friends leave the set */
+		let zeros = table.iter_cells().filter(|(_, _, result)| *result == 0).count();
+		assert_eq!(table.iter_nonzero_cells().count(), len * len - zeros);
+		assert!(zeros > 0);
+	}
+
+	#[test]
+	fn test_gen_pow_table()
+	{
+		/* [-2], (-2,-1), [-1], (-1,0), [0], ..., [4] */
+		let set = Rc::new(RefCell::new(SornSet::new(-2.0, 4.0, 1.0, false)));
+		let table = gen_pow_table(set.clone(), 2);
+		let four = Sorn::from_sornvalue(set.clone(), SornValue::Exact(4.0)).bits;
+
+		let square_of = |value: Real| table.iter_cells()
+			.find(|(row, _, _)| *row == Sorn::from_sornvalue(set.clone(), SornValue::Exact(value)).bits)
+			.map(|(_, _, result)| result);
+
+		assert_eq!(square_of(-2.0), Some(four));
+		assert_eq!(square_of(2.0), Some(four));
+		assert_eq!(table.iter_cells().count(), set.borrow().len());
+		assert_eq!(table.to_csv().lines().next().unwrap(), ",0,");
+	}
+
+
+	#[test]
+	fn test_diff()
+	{
+		let set = Rc::new(RefCell::new(SornSet::new(-1.0, 1.0, 1.0, false)));
+		let table = gen_table(set.clone(), "add");
+		let mut perturbed = gen_table(set.clone(), "add");
+
+		assert!(table.diff(&perturbed).unwrap().is_empty());
+
+		perturbed.table_data[1][3] ^= 0b1;
+		perturbed.table_data[4][0] = 0;
+		let expected = vec![(1, 3, table.table_data[1][3], table.table_data[1][3] ^ 0b1), (4, 0, table.table_data[4][0], 0)];
+		assert_eq!(table.diff(&perturbed).unwrap(), expected);
+
+		let pow = gen_pow_table(set.clone(), 2);
+		assert!(matches!(table.diff(&pow), Err(SornErrors::DimensionMismatch)));
+
+		let other = gen_table(Rc::new(RefCell::new(SornSet::new(0.0, 2.0, 1.0, false))), "add");
+		assert!(matches!(table.diff(&other), Err(SornErrors::DifferentSornSets)));
 	}
 }
\ No newline at end of file